@@ -0,0 +1,78 @@
+use std::{any::type_name, io};
+
+use crate::consts::{config_values, Model};
+
+// bumped whenever the layout of an archive directory changes
+pub const ARCHIVE_FORMAT_VERSION: u32 = 2;
+
+// FNV-1a over every const's name and value, and the Model type, rather than std's hasher, whose output isn't
+// promised to stay the same between Rust releases. an archive whose hash differs was written by a build
+// configured differently
+pub fn config_hash() -> u64 {
+    let model = ("Model", type_name::<Model>().to_string());
+
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for (name, value) in config_values().into_iter().chain([model]) {
+        for byte in format!("{}={}\n", name, value).bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    hash
+}
+
+// manifest.txt of an archive, one `key=value` per line
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub format_version: u32,
+    pub config_hash: u64,
+    pub generation: usize,
+    pub seed: Option<u64>, // what the archived world's rng was seeded from, if it was
+}
+
+impl Manifest {
+    pub fn current(generation: usize, seed: Option<u64>) -> Self {
+        Manifest {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            config_hash: config_hash(),
+            generation,
+            seed,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let seed = self
+            .seed
+            .map_or("none".to_string(), |seed| seed.to_string());
+        format!(
+            "format_version={}\nconfig_hash={:016x}\ngeneration={}\nseed={}\n",
+            self.format_version, self.config_hash, self.generation, seed
+        )
+    }
+
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let invalid =
+            |what: &str| io::Error::new(io::ErrorKind::InvalidData, format!("manifest: {}", what));
+        let field = |key: &str| {
+            text.lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+                .ok_or_else(|| invalid(&format!("missing {}", key)))
+        };
+
+        Ok(Manifest {
+            format_version: field("format_version")?
+                .parse()
+                .map_err(|_| invalid("bad format_version"))?,
+            config_hash: u64::from_str_radix(field("config_hash")?, 16)
+                .map_err(|_| invalid("bad config_hash"))?,
+            generation: field("generation")?
+                .parse()
+                .map_err(|_| invalid("bad generation"))?,
+            seed: match field("seed")? {
+                "none" => None,
+                seed => Some(seed.parse().map_err(|_| invalid("bad seed"))?),
+            },
+        })
+    }
+}
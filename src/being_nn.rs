@@ -2,7 +2,7 @@ use std::iter::zip;
 
 use burn::nn::Linear;
 use burn::prelude::*;
-use nn::attention::MultiHeadAttention;
+use nn::attention::{MhaInput, MultiHeadAttention};
 use nn::{LinearConfig, Lstm};
 
 use burn::module::{Module, Param};
@@ -10,6 +10,10 @@ use burn::nn::Relu;
 use burn::tensor::backend::Backend;
 use burn::tensor::Tensor;
 
+
+use crate::consts::{EMPTY_INPUT_POLICY, MASK_SENTINEL_IN_MEAN, POOLING};
+use crate::EmptyInputPolicy;
+
 pub fn tensorize_2dvec<B: Backend>(
     vec: &Vec<Vec<f32>>,
     shape: [usize; 2],
@@ -26,6 +30,58 @@ pub fn tensorize_2dvec<B: Backend>(
     .reshape(shape)
 }
 
+// every sensory set arrives with a `-1.` sentinel row appended last, so that it is never empty.
+// averaged in, the sentinel drags the mean of n real rows towards its own encoding by (f(sentinel) - mean) / (n + 1),
+// i.e. half the signal of a lone neighbour. masked, the sentinel only speaks for sets that are otherwise empty,
+// and then only if EMPTY_INPUT_POLICY lets it. the same masking applies under any POOLING.
+pub fn pool_set<B: Backend>(x: Tensor<B, 2>) -> Tensor<B, 2> {
+    pool_set_under(x, EMPTY_INPUT_POLICY)
+}
+
+pub fn pool_set_under<B: Backend>(x: Tensor<B, 2>, policy: EmptyInputPolicy) -> Tensor<B, 2> {
+    let n = x.dims()[0];
+    if MASK_SENTINEL_IN_MEAN && n > 1 {
+        POOLING.pool(x.narrow(0, 0, n - 1))
+    } else if n == 1 && !matches!(policy, EmptyInputPolicy::Sentinel) {
+        x.mean_dim(0).zeros_like()
+    } else {
+        POOLING.pool(x)
+    }
+}
+
+// self-attention over a sensory set, ahead of its FF and pool_set. pool_set leaving the sentinel out isn't
+// enough on its own, since by then every real row would have attended to it, so it's masked out as a key too
+pub fn attend_set<B: Backend>(mha: &MultiHeadAttention<B>, rows: Tensor<B, 2>) -> Tensor<B, 2> {
+    let n = rows.dims()[0];
+    let device = rows.device();
+    let rows: Tensor<B, 3> = rows.unsqueeze();
+    let mut input = MhaInput::new(rows.clone(), rows.clone(), rows);
+    if MASK_SENTINEL_IN_MEAN && n > 1 {
+        let sentinel = Tensor::<B, 1, Int>::arange(0..n as i64, &device).equal_elem(n as i64 - 1);
+        input = input.mask_pad(sentinel.unsqueeze());
+    }
+
+    mha.forward(input).context.squeeze(0)
+}
+
+// how a sensory set's rows are reduced to one
+#[derive(Debug, Clone, Copy)]
+pub enum Pooling {
+    Mean, // the average entity; one salient row is diluted by the rest
+    Max,  // per feature, the strongest entity
+    Sum,  // as Mean, but grows with the number of entities
+}
+
+impl Pooling {
+    pub fn pool<B: Backend>(&self, x: Tensor<B, 2>) -> Tensor<B, 2> {
+        match self {
+            Pooling::Mean => x.mean_dim(0),
+            Pooling::Max => x.max_dim(0),
+            Pooling::Sum => x.sum_dim(0),
+        }
+    }
+}
+
 #[derive(Module, Clone, Debug, Default)]
 pub struct Tanh {}
 
@@ -62,6 +118,17 @@ trait Forward {
     fn forward<B: Backend, const D: usize>(&self, input: Tensor<B, D>) -> Tensor<B, D>;
 }
 
+impl Activation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Activation::Relu(_) => "relu",
+            Activation::Tanh(_) => "tanh",
+            Activation::Sigmoid(_) => "sigmoid",
+            Activation::Identity => "identity",
+        }
+    }
+}
+
 impl Forward for Activation {
     fn forward<B: Backend, const D: usize>(&self, input: Tensor<B, D>) -> Tensor<B, D> {
         match self {
@@ -146,18 +213,195 @@ impl<B: Backend> FF<B> {
 
         return x;
     }
+
+    // the layer widths, each followed by the activation applied on the way out of it, e.g. "9 -> 8 tanh"
+    pub fn describe(&self) -> String {
+        let mut description = self.config.0[0].to_string();
+        for (size, act) in zip(&self.config.0[1..], &self.acts) {
+            description += &format!(" -> {} {}", size, act.name());
+        }
+        description
+    }
+
+    pub fn num_params(&self) -> usize {
+        self.lins.iter().map(|lin| lin.num_params()).sum()
+    }
+
+    // every layer's weights then bias, in order; load_params reads them back the same way
+    pub fn params(&self) -> Vec<f32> {
+        let mut params = vec![];
+        for lin in &self.lins {
+            params.extend(lin.weight.val().into_data().convert::<f32>().value);
+            if let Some(bias) = &lin.bias {
+                params.extend(bias.val().into_data().convert::<f32>().value);
+            }
+        }
+
+        params
+    }
+
+    // overwrites the parameters from the front of `params`, returning how many were taken
+    pub fn load_params(&mut self, params: &[f32]) -> usize {
+        let mut n = 0;
+        for lin in &mut self.lins {
+            let weight = lin.weight.val();
+            let (dims, device) = (weight.dims(), weight.device());
+            let len = dims[0] * dims[1];
+            lin.weight = Param::from_tensor(Tensor::<B, 1>::from_floats(&params[n..n + len], &device).reshape(dims));
+            n += len;
+
+            if let Some(bias) = &lin.bias {
+                let len = bias.val().dims()[0];
+                lin.bias = Some(Param::from_tensor(Tensor::<B, 1>::from_floats(&params[n..n + len], &device)));
+                n += len;
+            }
+        }
+
+        n
+    }
+}
+
+// runs a branch on its inputs, or, if SENSES has it switched off, skips it and stands in a row of zeros as
+// wide as its FF would have put out
+pub fn sense_branch<B: Backend>(
+    enabled: bool,
+    ff: &FF<B>,
+    x: Tensor<B, 2>,
+    run: impl FnOnce(Tensor<B, 2>) -> Tensor<B, 2>,
+) -> Tensor<B, 2> {
+    if enabled {
+        run(x)
+    } else {
+        Tensor::zeros([1, *ff.config.0.last().unwrap()], &x.device())
+    }
+}
+
+// a model's architecture, one line per part: the five FFs in (being, food_obstruct, speechlet, self, final)
+// order, then any other (name, description, parameter count) parts it has, then the model's num_params
+pub fn describe_model<B: Backend>(
+    name: &str,
+    concat: bool,
+    intermediate_dim: usize,
+    ffs: [&FF<B>; 5],
+    others: &[(&str, String, usize)],
+    total: usize,
+) -> String {
+    let mode = if concat { "concatenated" } else { "added" };
+    let mut lines = vec![format!("{}, branches {} into {}", name, mode, intermediate_dim)];
+
+    for (branch, ff) in zip(["being", "food_obstruct", "speechlet", "self", "final"], ffs) {
+        lines.push(format!("  {}: {}, {} params", branch, ff.describe(), ff.num_params()));
+    }
+    for (part, description, n) in others {
+        lines.push(format!("  {}: {}, {} params", part, description, n));
+    }
+    lines.push(format!("  total: {} params", total));
+
+    lines.join("\n")
+}
+
+// joins the (being, food_obstruct, speechlet, self) branch outputs into the final model's input. each must
+// have pooled down to one row, and in add mode all four must be as wide, or the merge would broadcast or fail
+// somewhere inside burn without naming the branch at fault
+pub fn merge_branches<B: Backend>(outputs: [Tensor<B, 2>; 4], concat: bool) -> Tensor<B, 2> {
+    const BRANCHES: [&str; 4] = ["being", "food_obstruct", "speechlet", "self"];
+    let dims: Vec<[usize; 2]> = outputs.iter().map(|x| x.dims()).collect();
+
+    for (branch, [rows, _]) in zip(BRANCHES, &dims) {
+        assert_eq!(*rows, 1, "the {} branch put out {} rows instead of one", branch, rows);
+    }
+
+    if concat {
+        return Tensor::cat(outputs.to_vec(), 1);
+    }
+    for (branch, [_, width]) in zip(BRANCHES, &dims) {
+        assert_eq!(
+            *width, dims[0][1],
+            "in add mode the {} branch is {} wide but the being branch is {}",
+            branch, width, dims[0][1]
+        );
+    }
+    let [beings, fo, speechlet, own] = outputs;
+    (beings + fo + speechlet + own) / 4.
+}
+
+// the unit of inheritance when two FFs are spliced
+#[derive(Debug, Clone, Copy)]
+pub enum CrossoverGranularity {
+    Weight, // every weight from either parent
+    Neuron, // every output neuron, i.e. a weight column and its bias, from one parent
+    Layer,  // every whole layer from one parent
 }
 
+// scales the mutation rate per branch, so that parts of a model can evolve at different speeds. a branch
+// scaled by 0. is frozen
+#[derive(Debug, Clone, Copy)]
+pub struct MutationProfile {
+    pub being: f32,
+    pub food_obstruct: f32,
+    pub speechlet: f32,
+    pub own: f32,
+    pub decision: f32, // the final model
+    pub lstm: f32,
+    pub attention: f32,
+}
+
+impl MutationProfile {
+    pub const UNIFORM: Self = MutationProfile {
+        being: 1.,
+        food_obstruct: 1.,
+        speechlet: 1.,
+        own: 1.,
+        decision: 1.,
+        lstm: 1.,
+        attention: 1.,
+    };
+
+    // in the order every model lists its FFs: being, fo, speechlet, self, final
+    pub fn ff_scales(&self) -> [f32; 5] {
+        [self.being, self.food_obstruct, self.speechlet, self.own, self.decision]
+    }
+}
+
+// which branches a model runs. a switched-off branch never sees its inputs and feeds zeros into the merge
+#[derive(Debug, Clone, Copy)]
+pub struct Senses {
+    pub being: bool,
+    pub food_obstruct: bool,
+    pub speechlet: bool,
+    pub own: bool,
+}
+
+impl Senses {
+    pub const ALL: Self = Senses {
+        being: true,
+        food_obstruct: true,
+        speechlet: true,
+        own: true,
+    };
+}
+
+// each unit comes from ff1 with probability left_weight, and from ff2 otherwise. every draw is the backend's,
+// so a seeded backend splices reproducibly
 pub fn splice_ffs<B: Backend>(
     mut ff1: FF<B>,
     ff2: FF<B>,
     left_weight: f32,
+    granularity: CrossoverGranularity,
 ) -> FF<B> {
     for (ff1_lin, ff2_lin) in zip(&mut ff1.lins, ff2.lins) {
         let weight = ff1_lin.weight.clone().val();
+        let [d_in, d_out] = weight.dims();
+
+        // one uniform draw per unit, broadcast over the weights that unit owns
+        let draw: Tensor<B, 2> = match granularity {
+            CrossoverGranularity::Weight => weight.random_like(burn::tensor::Distribution::Uniform(0., 1.)),
+            CrossoverGranularity::Neuron => Tensor::<B, 2>::random([1, d_out], burn::tensor::Distribution::Uniform(0., 1.), &weight.device()).repeat(0, d_in),
+            CrossoverGranularity::Layer => Tensor::<B, 2>::random([1, 1], burn::tensor::Distribution::Uniform(0., 1.), &weight.device()).repeat(0, d_in).repeat(1, d_out),
+        };
         let mask: Tensor<B, 2> = weight.ones_like().mul_scalar(left_weight);
-        
-        let ff1_mask: Tensor<B, 2, Bool> = weight.random_like(burn::tensor::Distribution::Uniform(0., 1.)).greater_equal(mask);
+
+        let ff1_mask: Tensor<B, 2, Bool> = draw.clone().greater_equal(mask);
         let ff2_mask: Tensor<B, 2, Bool> = ff1_mask.clone().bool_not();
 
         let weight = ff1_lin.weight.val().mask_fill(ff1_mask, 0.) + ff2_lin.weight.val().mask_fill(ff2_mask, 0.);
@@ -166,8 +410,12 @@ pub fn splice_ffs<B: Backend>(
         if !ff1_lin.bias.is_none() {
             let bias = ff1_lin.bias.clone().unwrap().val();
             let mask: Tensor<B, 1> = bias.ones_like().mul_scalar(left_weight);
+            let bias_draw: Tensor<B, 1> = match granularity {
+                CrossoverGranularity::Weight => bias.random_like(burn::tensor::Distribution::Uniform(0., 1.)),
+                _ => draw.narrow(0, 0, 1).reshape([d_out]),
+            };
 
-            let ff1_mask: Tensor<B, 1, Bool> = bias.random_like(burn::tensor::Distribution::Uniform(0., 1.)).greater_equal(mask);
+            let ff1_mask: Tensor<B, 1, Bool> = bias_draw.greater_equal(mask);
             let ff2_mask: Tensor<B, 1, Bool> = ff1_mask.clone().bool_not();
 
             let bias = ff1_lin.bias.clone().unwrap().val().mask_fill(ff1_mask, 0.) + ff2_lin.bias.unwrap().val().mask_fill(ff2_mask, 0.);
@@ -291,3 +539,142 @@ pub fn combine_mhas<B: Backend>(
     set-transformer implementation for each input type, then final_output_model(intermediate) similarly.
     I remember reading something along the lines that their model subsumes sum({f(x) for all x})
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BACKEND, DEVICE};
+    use nn::attention::MultiHeadAttentionConfig;
+
+    fn rows(values: &[[f32; 2]]) -> Tensor<BACKEND, 2> {
+        let flat: Vec<f32> = values.iter().flatten().copied().collect();
+        Tensor::<BACKEND, 1>::from_floats(flat.as_slice(), &DEVICE).reshape([values.len(), 2])
+    }
+
+    fn values(x: Tensor<BACKEND, 2>) -> Vec<f32> {
+        x.into_data().convert::<f32>().value
+    }
+
+    #[test]
+    fn the_sentinel_is_left_out_of_pooling() {
+        let real = [[1., 2.], [3., 4.]];
+        let with_sentinel = rows(&[real[0], real[1], [-1., -1.]]);
+
+        let masked = values(pool_set(with_sentinel.clone()));
+        let unmasked = values(POOLING.pool(with_sentinel));
+        assert_eq!(masked, values(POOLING.pool(rows(&real))));
+        assert_ne!(masked, unmasked);
+    }
+
+    #[test]
+    fn real_rows_dont_attend_to_the_sentinel() {
+        let _turn = crate::tests::backend_turn();
+        let mha = MultiHeadAttentionConfig::new(2, 1).init::<BACKEND>(&DEVICE);
+        let attended = |sentinel: [f32; 2]| values(attend_set(&mha, rows(&[[1., 2.], [3., 4.], sentinel])));
+
+        let (a, b) = (attended([-1., -1.]), attended([5., -7.]));
+        for (x, y) in a[..4].iter().zip(&b[..4]) {
+            assert!((x - y).abs() < 1e-5, "{:?} vs {:?}", a, b);
+        }
+    }
+
+    // seeing nothing pools to the same thing whatever the sentinel holds, unless the sentinel is the policy
+    #[test]
+    fn an_empty_set_pools_independently_of_its_sentinel() {
+        for policy in [EmptyInputPolicy::Zeros, EmptyInputPolicy::Flag] {
+            let pooled = |sentinel: [f32; 2]| values(pool_set_under(rows(&[sentinel]), policy));
+            assert_eq!(pooled([-1., -1.]), vec![0., 0.]);
+            assert_eq!(pooled([5., -7.]), vec![0., 0.]);
+        }
+
+        let pooled = values(pool_set_under(rows(&[[-1., -1.]]), EmptyInputPolicy::Sentinel));
+        assert_eq!(pooled, vec![-1., -1.]);
+    }
+
+    // splices an all-zero FF with an all-one FF, and gives back each layer's weight rows and bias, which
+    // then read 0. where a unit came from the first parent and 1. where it came from the second
+    fn spliced_layers(granularity: CrossoverGranularity) -> Vec<(Vec<Vec<f32>>, Vec<f32>)> {
+        BACKEND::seed(0);
+        let sizes = vec![8, 8, 8];
+        let parent = |value: f32| {
+            let mut ff = FF::<BACKEND>::new(sizes.clone(), vec![Activation::Identity; 3], &DEVICE);
+            ff.load_params(&vec![value; ff.num_params()]);
+            ff
+        };
+        let child = splice_ffs(parent(0.), parent(1.), 0.5, granularity);
+
+        child
+            .lins
+            .iter()
+            .map(|lin| {
+                let [d_in, d_out] = lin.weight.val().dims();
+                let weight = lin.weight.val().into_data().convert::<f32>().value;
+                let rows = (0..d_in).map(|i| weight[i * d_out..(i + 1) * d_out].to_vec()).collect();
+                (rows, lin.bias.as_ref().unwrap().val().into_data().convert::<f32>().value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn each_crossover_granularity_splices_whole_units() {
+        let _turn = crate::tests::backend_turn();
+
+        // a neuron is a weight column and its bias
+        let neurons_whole = |layers: &[(Vec<Vec<f32>>, Vec<f32>)]| {
+            layers.iter().all(|(rows, bias)| rows.iter().all(|row| row == bias))
+        };
+
+        let by_weight = spliced_layers(CrossoverGranularity::Weight);
+        assert!(!neurons_whole(&by_weight));
+
+        let by_neuron = spliced_layers(CrossoverGranularity::Neuron);
+        assert!(neurons_whole(&by_neuron));
+        assert!(by_neuron.iter().any(|(_, bias)| bias.contains(&0.) && bias.contains(&1.)));
+
+        let by_layer = spliced_layers(CrossoverGranularity::Layer);
+        assert!(neurons_whole(&by_layer));
+        assert!(by_layer.iter().all(|(_, bias)| bias.iter().all(|&x| x == bias[0])));
+    }
+
+
+    // one threat among three quiet neighbours: mean dilutes it, max keeps it whole, sum adds it in
+    #[test]
+    fn max_pooling_keeps_an_outlier_that_mean_dilutes() {
+        let _turn = crate::tests::backend_turn();
+        let set = rows(&[[0., 0.1], [0., 0.2], [8., 0.3], [0., 0.2]]);
+
+        let mean = values(Pooling::Mean.pool(set.clone()));
+        let max = values(Pooling::Max.pool(set.clone()));
+        let sum = values(Pooling::Sum.pool(set));
+
+        assert_eq!(max, vec![8., 0.3]);
+        assert_eq!(mean[0], 2.);
+        assert!((mean[1] - 0.2).abs() < 1e-6);
+        assert_eq!(sum[0], 8.);
+        assert!((sum[1] - 0.8).abs() < 1e-6);
+    }
+
+
+    #[test]
+    #[should_panic(expected = "the self branch put out 2 rows instead of one")]
+    fn an_unpooled_self_branch_is_named_before_the_add() {
+        let one = rows(&[[1., 2.]]);
+        merge_branches([one.clone(), one.clone(), one, rows(&[[1., 2.], [3., 4.]])], false);
+    }
+
+    #[test]
+    #[should_panic(expected = "in add mode the speechlet branch is 1 wide but the being branch is 2")]
+    fn a_narrow_branch_is_named_before_the_add() {
+        let one = rows(&[[1., 2.]]);
+        let narrow = one.clone().slice([0..1, 0..1]);
+        merge_branches([one.clone(), one.clone(), narrow, one], false);
+    }
+
+    // concat mode only needs one row from each, and lines their widths up
+    #[test]
+    fn concat_mode_takes_branches_of_any_width() {
+        let one = rows(&[[1., 2.]]);
+        let narrow = one.clone().slice([0..1, 0..1]);
+        assert_eq!(merge_branches([one.clone(), one.clone(), narrow, one], true).dims(), [1, 7]);
+    }
+}
@@ -0,0 +1,42 @@
+use slotmap::DefaultKey;
+
+// one kind of entity bucketed by partition cell into a single flat array, rebuilt from scratch every step
+// with a counting sort. a rebuild is O(n + cells) and every cell is a contiguous slice, where a Vec per cell
+// paid O(cell occupancy) in retain for each entity that moved or died and allocated as cells grew.
+pub struct CellIndex {
+    starts: Vec<usize>, // the keys of cell ij are keys[starts[ij]..starts[ij + 1]]
+    keys: Vec<DefaultKey>,
+}
+
+impl CellIndex {
+    pub fn new(n_cells: usize) -> Self {
+        CellIndex {
+            starts: vec![0; n_cells + 1],
+            keys: vec![],
+        }
+    }
+
+    // `entities` yields each key alongside the 1D index of the cell that contains it
+    pub fn rebuild(&mut self, entities: impl Iterator<Item = (DefaultKey, usize)>) {
+        let entities: Vec<(DefaultKey, usize)> = entities.collect();
+
+        self.starts.iter_mut().for_each(|start| *start = 0);
+        for (_, ij) in &entities {
+            self.starts[ij + 1] += 1;
+        }
+        for ij in 1..self.starts.len() {
+            self.starts[ij] += self.starts[ij - 1];
+        }
+
+        let mut cursors = self.starts.clone();
+        self.keys = vec![DefaultKey::default(); entities.len()];
+        for (k, ij) in entities {
+            self.keys[cursors[ij]] = k;
+            cursors[ij] += 1;
+        }
+    }
+
+    pub fn cell(&self, ij: usize) -> &[DefaultKey] {
+        &self.keys[self.starts[ij]..self.starts[ij + 1]]
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{Parallelism, MAX_STEPS_PER_DRAW};
+
+// a line typed into the in-window console. only what can change mid-run without rebuilding the world is
+// settable from here; everything else is still a const and needs a recompile
+#[derive(Debug, Clone, Copy)]
+pub enum Command {
+    MaxFood(usize),
+    StepsPerDraw(usize),
+    Parallelism(Parallelism),
+    EnergyBars(bool),
+}
+
+// the names `set` accepts, for the reply to anything it doesn't
+pub const SETTABLE: [&str; 4] = ["max_food", "steps_per_draw", "parallelism", "energy_bars"];
+
+// `set <name> <value>`, or a message saying what's wrong with the line
+pub fn parse(line: &str) -> Result<Command, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (name, value) = match words[..] {
+        ["set", name, value] => (name, value),
+        _ => {
+            return Err(format!(
+                "expected `set <name> <value>`, got `{}`",
+                line.trim()
+            ))
+        }
+    };
+
+    let bad_value = |expected: &str| format!("{} takes {}, not `{}`", name, expected, value);
+    match name {
+        "max_food" => value
+            .parse()
+            .map(Command::MaxFood)
+            .map_err(|_| bad_value("a whole number")),
+        "steps_per_draw" => match value.parse() {
+            Ok(steps) if (1..=MAX_STEPS_PER_DRAW).contains(&steps) => {
+                Ok(Command::StepsPerDraw(steps))
+            }
+            _ => Err(bad_value(&format!(
+                "a whole number from 1 to {}",
+                MAX_STEPS_PER_DRAW
+            ))),
+        },
+        "parallelism" => match value {
+            "serial" => Ok(Command::Parallelism(Parallelism::Serial)),
+            "rayon" => Ok(Command::Parallelism(Parallelism::Rayon)),
+            _ => Err(bad_value("`serial` or `rayon`")),
+        },
+        "energy_bars" => value
+            .parse()
+            .map(Command::EnergyBars)
+            .map_err(|_| bad_value("`true` or `false`")),
+        _ => Err(format!(
+            "{} can't be changed mid-run; settable are {}",
+            name,
+            SETTABLE.join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_per_draw_is_bounded() {
+        assert!(matches!(
+            parse("set steps_per_draw 8"),
+            Ok(Command::StepsPerDraw(8))
+        ));
+        assert!(parse("set steps_per_draw 0").is_err());
+        assert!(parse(&format!("set steps_per_draw {}", MAX_STEPS_PER_DRAW + 1)).is_err());
+    }
+}
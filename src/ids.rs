@@ -0,0 +1,33 @@
+// typed entity ids, so a being's id can't be compared against a food's by mistake. the World keeps one of
+// each as a counter and hands out the next id on every add
+macro_rules! entity_ids {
+    ($($name:ident),*) => {
+        $(
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name(pub u64);
+
+            impl $name {
+                // returns the counter's current id and moves it on to the next
+                pub fn take_next(&mut self) -> Self {
+                    let id = *self;
+                    self.0 += 1;
+                    id
+                }
+            }
+
+            impl From<u64> for $name {
+                fn from(id: u64) -> Self {
+                    $name(id)
+                }
+            }
+
+            impl From<$name> for u64 {
+                fn from(id: $name) -> Self {
+                    id.0
+                }
+            }
+        )*
+    };
+}
+
+entity_ids!(BeingId, ObstructId, FoodId, CorpseId);
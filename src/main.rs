@@ -1,35 +1,112 @@
+mod archive;
 mod being_nn;
+mod cell_index;
+mod console;
+mod ids;
 mod models;
+mod scenario;
+mod scene;
+mod schema;
+mod stats;
+mod trace;
 
+use archive::{config_hash, Manifest, ARCHIVE_FORMAT_VERSION};
 use being_nn::*;
+use cell_index::CellIndex;
+use console::Command;
 use ggez::{
     conf::{NumSamples, WindowMode, WindowSetup},
     event,
     glam::*,
-    graphics::{Canvas, Color, DrawParam, Image, InstanceArray},
+    graphics::{
+        Canvas, Color, DrawMode, DrawParam, Image, ImageFormat, InstanceArray, Mesh, MeshBuilder,
+        Rect, Text,
+    },
+    input::keyboard::{KeyCode, KeyInput},
     Context, GameResult,
 };
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use ids::{BeingId, CorpseId, FoodId, ObstructId};
+use models::BeingModel;
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    rngs::StdRng,
+    seq::{index, IteratorRandom},
+    Rng, SeedableRng,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use scenario::{Observation, Scenario, ScenarioMismatch};
+use scene::{Scene, SceneEntity};
+use schema::SensorySchema;
 use slotmap::{DefaultKey, SlotMap};
+use stats::{
+    pearson, weight_diversity, Anomaly, GenerationStats, OccupancyStats, PhaseTimings, RunSummary,
+};
 use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     env,
     f32::consts::PI,
-    path::PathBuf,
-    process::{exit, id},
-    thread::sleep,
-    time::{Duration, SystemTime},
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
     vec,
 };
+use trace::{hash_floats, StepHashes};
 
 use burn::prelude::*;
 
+// declares the consts along with config_values, every one's name and Debug-formatted value, which is what
+// archive::config_hash fingerprints. declaring them through here means none can be left out of it
+macro_rules! consts {
+    ($(pub const $name:ident: $ty:ty = $value:expr;)*) => {
+        $(pub const $name: $ty = $value;)*
+
+        pub fn config_values() -> Vec<(&'static str, String)> {
+            vec![$((stringify!($name), format!("{:?}", $name))),*]
+        }
+    };
+}
+
 #[rustfmt::skip]
 pub mod consts {
     use burn::backend;
 
     use crate::models::{sumfx::SumFxModel, sumfxlstm::SumFxLstmModel, mha::MhaModel, mhalstm::MhaLstmModel};
+    use crate::{
+        AgingModel, BeingSpawn, DeathRemains, DeathReproductionOrder, EmptyInputPolicy, FoodCapMode, FoodValueDist, InitialFacing, ObstructPlacement,
+        Parallelism, Stage, TerrainLayout, TimeBudget, WallSense,
+    };
+    use crate::being_nn::{CrossoverGranularity, MutationProfile, Pooling, Senses};
+    use ggez::graphics::Rect;
 
+    pub type BACKEND                                        = backend::NdArray;
+    pub type Model                                          = SumFxModel<BACKEND>;
+
+    consts! {
     pub const VIS_FREQUENCY:                          usize = 1;
+    pub const SHOW_OCCUPANCY_HUD:                      bool = false;                // draw being-per-cell occupancy stats on screen
+    pub const STATS_CSV_PATH:                  Option<&str> = None;                 // per-generation action frequencies are appended here
+    pub const ANOMALY_DIE_OFF_FRACTION:                 f32 = 0.5;                  // a generation with fewer being-steps than this share of the last is a die-off
+    pub const DRAW_ALPHA_THRESHOLD:                     f32 = 0.;                   // entities that would be drawn fainter than this are skipped
+    pub const DRAW_HEALTH_AS_ALPHA:                    bool = false;                // beings fade with their health rather than their energy
+    pub const COLOR_BY_GENOME:                         bool = false;                // beings are drawn in their genome_color instead of the age tint
+    pub const COLOR_SPEECHLETS:                        bool = false;                // speechlets are drawn in their speechlet_color instead of plain blue
+    pub const TRAJECTORY_LEN:                         usize = 0;                    // positions kept per being for World::trajectory; 0 records none
+    pub const TRAJECTORY_EVERY:                       usize = 10;                   // steps between recorded positions
+    pub const DRAW_TRAILS:                             bool = false;                // draw each being's recorded trajectory, fading with age
+    pub const SIM_STEPS_PER_DRAW:                     usize = 1;                    // starting fast-forward multiplier, adjusted with up/down
+    pub const MAX_STEPS_PER_DRAW:                     usize = 1024;                 // up, or the console, can't push the multiplier past this
+    pub const DRAW_ENERGY_BARS:                        bool = false;                // start with a bar over each being showing its energy, toggled with E
+    // ` opens a console taking `set <name> <value>` for the few settings that can change mid-run, see console::SETTABLE
+    pub const PROFILE_PHASES:                          bool = false;                // time each phase of a step, see World::phase_timings; shown in the hud
+    pub const TRACE_STEPS:                             bool = false;                // fingerprint the world every step, see trace::first_divergence
+    pub const TIME_BUDGET:               Option<TimeBudget> = None;                 // degrade perception to keep steps within a wall-clock budget
+    pub const PARALLELISM:                      Parallelism = Parallelism::Rayon;   // what a World starts with, see World::set_parallelism
+    pub const WORLD_SEED:                       Option<u64> = None;                 // seeds the world's rng and burn's, for runs that can be repeated exactly
+    pub const PER_BEING_RNG:                           bool = false;                // give every being its own rng, seeded from the world's, its generation and its id
+    pub const BENCHMARK_SEED:                           u64 = 0;                    // seeds World::benchmark_world
+    pub const BENCHMARK_STEPS:                        usize = 100;                  // how far benchmark() runs it; BENCHMARK_SUMMARY is where that should end up
+    pub const DISTINCT_SHAPES:                         bool = false;                // obstructs are drawn as squares and speechlets as rings rather than circles
 
     pub const W_SIZE:                                 usize = 333;
     pub const N_CELLS:                                usize = 111;
@@ -37,66 +114,488 @@ pub mod consts {
     pub const CELL_SIZE_FLOAT:                          f32 = CELL_SIZE as f32;
     pub const W_FLOAT:                                  f32 = W_SIZE as f32;
     pub const W_USIZE:                                  u32 = W_SIZE as u32;
+    pub const ARENA:                           Option<Rect> = None;                 // walls beings and food in to this part of the world; None uses all of it
 
     pub const B_START_COUNT:                          usize = 250;
     pub const REWORLDING_THRESHOLD:                   usize = 50;
+    pub const STAGES:                              &[Stage] = &[];                  // a curriculum; empty runs every generation on the settings here
+    pub const MAX_GENERATION_AGE:             Option<usize> = None;                 // reworld at this age even if enough beings survive
+    pub const IMMIGRATION_FLOOR:                        f32 = 0.5;                  // fraction of B_START_COUNT below which a population counts as low
+    pub const IMMIGRATION_PATIENCE:                   usize = 500;                  // steps a population may stay low before immigrants arrive
+    pub const IMMIGRATION_RATE:                       usize = 0;                    // fresh random beings injected each time; 0 disables immigration
+    pub const REWORLD_CLEARS_FOOD:                     bool = true;                 // false carries the food field over into the next generation as it stands
+    pub const B_INITIAL_FACING:               InitialFacing = InitialFacing::Uniform;
+    pub const B_SPAWN:                           BeingSpawn = BeingSpawn::Uniform;  // where a generation's founders are placed
+    pub const OFFSPRING_NEAR_PARENT:            Option<f32> = None;                 // offspring land within this of their first parent; None places them as B_SPAWN does
+    pub const N_SPECIES:                              usize = 1;                    // populations that share the world but never interbreed
 
     pub const B_FOV:                                  isize = 10;
     pub const B_FOV_PX:                                 f32 = (B_FOV as usize * CELL_SIZE) as f32;
+    pub const FOVEATION_STRENGTH:                       f32 = 0.;                   // sensory rows are scaled down by up to this much the further behind they lie
+    pub const REAR_BLIND_ARC:                           f32 = 0.;                   // radians, centred straight behind, in which beings see nothing; hearing is unaffected
     pub const B_SPEED:                                  f32 = 0.5;
     pub const B_RADIUS:                                 f32 = 2.75;
+    pub const RESIZING:                                bool = false;                // beings get an extra output that grows or shrinks them, and pay upkeep by area
+    pub const B_MIN_RADIUS:                             f32 = B_RADIUS / 2.;
+    pub const B_MAX_RADIUS:                             f32 = B_RADIUS * 2.;         // collisions are only looked for within B_FOV, so keep it well under B_FOV_PX / 2
+    pub const B_RESIZE_RATE:                            f32 = 0.05;                 // radius change per step at full output strength
+    pub const B_RESIZE_COST:                            f32 = 0.01;                 // energy per unit of radius changed, either way
     pub const O_RADIUS:                                 f32 = 2.;
     pub const F_RADIUS:                                 f32 = 2.;
     pub const S_RADIUS:                                 f32 = 1.5;
+    pub const S_MAX_RADIUS:                             f32 = S_RADIUS * (1. + S_LIFETIME_STEPS as f32); // reached on a speechlet's last step
+    pub const SAME_SPECIES_PHASE:                      bool = false;                // beings pass through their own species, but not others
+    pub const O_COLLISION_LAYER:                        u32 = 1 << 31;              // beings' layers are 1 << species
+    pub const CONTINUOUS_COLLISION:                    bool = false;                // sweep a being's pending move against foods and obstructs, so it can't tunnel
 
     pub const GENOME_LEN:                             usize = 10;                  // future prospect
-    pub const S_GROW_RATE:                              f32 = 1.;
+    pub const S_EXCLUDE_EMITTER:                       bool = true;                 // a being does not hear its own speechlets
+    pub const OBSTRUCTS_BLOCK_SPEECH:                  bool = false;                // a speechlet isn't heard through an obstruct between its origin and the listener
 
     pub const B_DEATH_ENERGY:                           f32 = 0.5;
     pub const B_SCATTER_RADIUS:                         f32 = 10.;
     pub const B_SCATTER_COUNT:                        usize = 100;
+    pub const B_REMAINS_ENERGY:                         f32 = B_SCATTER_COUNT as f32 * B_DEATH_ENERGY / B_SCATTER_RADIUS; // all the energy a death leaves, whatever DEATH_REMAINS makes of it
+    pub const DEATH_REMAINS:               DeathRemains = DeathRemains::Flesh;
+    pub const DEATH_ABSORPTION_FRACTION:                f32 = 0.5;                  // share of a being's remains the nearest being absorbs, under DeathRemains::Absorbed
+    pub const C_RADIUS:                                 f32 = B_RADIUS;
+    pub const C_START_ENERGY:                           f32 = B_REMAINS_ENERGY;     // as much as the flesh it replaces
+    pub const C_FEED_RATE:                              f32 = C_START_ENERGY / 20.;  // energy a being overlapping a corpse draws from it per step
+    pub const C_ROT_RATE:                               f32 = C_START_ENERGY / 1000.; // energy a corpse loses per step by itself
 
     pub const BASE_ANG_SPEED_DEGREES:                   f32 = 10.;
+    pub const B_MAX_ROT_PER_STEP:                       f32 = std::f32::consts::PI; // cap on the rotation a being accumulates over a step's substeps
 
     pub const B_START_ENERGY:                           f32 = 10.;
+    pub const SEPARATE_HEALTH:                         bool = false;                // collision damage comes out of a separate health, and either running out kills
+    pub const B_START_HEALTH:                           f32 = 10.;
+    pub const B_MAX_ENERGY:                             f32 = f32::INFINITY;        // energy is clamped to this once per step
     pub const O_START_HEALTH:                           f32 = 25.;
     pub const S_START_AGE:                              f32 = 5.;
+    pub const S_LIFETIME_STEPS:                       usize = 50;                   // steps a speechlet persists; its softening and growth rates follow
     pub const F_VAL:                                    f32 = 2.;
+    pub const F_VALUE_DIST:               FoodValueDist = FoodValueDist::Fixed(F_VAL); // what each plant is worth as it spawns
     pub const F_START_AGE:                              f32 = 50.;
     
     pub const B_TIRE_RATE:                              f32 = 0.005;
     pub const B_MOVE_TIRE_RATE:                         f32 = 0.000;
     pub const B_ROT_TIRE_RATE:                          f32 = 0.000;
+    pub const B_PERCEPTION_COST:                        f32 = 0.000;                // paid per entity perceived in a step
+    pub const B_AGING_MODEL:                     AgingModel = AgingModel::None;
+    pub const B_AGE_TINT_SPAN:                          f32 = 5000.;                // age (in steps) at which a being is drawn fully tinted
     pub const O_AGE_RATE:                               f32 = 0.001;
     pub const F_ROT_RATE:                               f32 = F_START_AGE / 1000.;
-    pub const S_SOFTEN_RATE:                            f32 = 0.1;
+    pub const S_SOFTEN_RATE:                            f32 = S_START_AGE / S_LIFETIME_STEPS as f32;
+    pub const S_GROW_RATE:                              f32 = (S_MAX_RADIUS - S_RADIUS) / S_LIFETIME_STEPS as f32;
 
     pub const B_HEADON_DAMAGE:                          f32 = 0.25;
     pub const B_REAR_DAMAGE:                            f32 = 1.;
+    pub const COMBAT_SPEED_WEIGHT:                      f32 = 0.;                   // 0. ignores the striker's speed, 1. makes damage proportional to it
+    pub const B_SPAWN_GRACE_STEPS:                    usize = 0;                    // newborns still get pushed apart but take no collision damage for this long
+    pub const EXPLORATION_BONUS:                        f32 = 0.;                   // energy for entering a cell that isn't among the recently visited
+    pub const EXPLORATION_MEMORY:                     usize = 16;                   // how many distinct recent cells a being remembers
     pub const HEADON_B_HITS_O_DAMAGE:                   f32 = 0.1;
     pub const SPAWN_O_RATIO:                            f32 = 0.01;                 // fraction of start_energy spent to spawn obstruct
+    pub const O_SPAWN_NEEDS_CLEARANCE:                 bool = true;                 // obstructs aren't spawned into other beings or obstructs, and are refunded
+    pub const O_PLACEMENT:                ObstructPlacement = ObstructPlacement::Free;  // Grid snaps spawns into aligned walls; a taken node refunds the spawn
+    pub const MINING:                                  bool = false;                // beings get an extra output that breaks down obstructs they overlap for energy
+    pub const MINE_RATE:                                f32 = 1.;                   // obstruct age mined per step at full output strength
+    pub const MINE_YIELD:                               f32 = SPAWN_O_RATIO * B_START_ENERGY / O_START_HEALTH; // energy per unit of age; a fresh obstruct returns its spawn cost
     pub const SPAWN_S_RATIO:                            f32 = 0.01;                // fraction of start_energy spent to speak
     pub const OOB_PENALTY:                              f32 = 0.25;
 
     pub const LOW_ENERGY_SPEED_DAMP_RATE:               f32 = 0.001;                 // beings slow down when their energy runs low
     pub const OFF_DIR_MOVEMENT_SPEED_DAMP_RATE:         f32 = 0.001;                 // beings slow down when not moving face-forward
+    pub const REVERSE_SPEED_DAMP:                       f32 = 0.5;                   // fraction of its speed a being loses backing up
+    pub const B_REVERSE_TIRE_FACTOR:                    f32 = 1.;                    // multiplies the movement cost of backing up
+    pub const TERRAIN:                        TerrainLayout = TerrainLayout::Uniform; // per-cell multipliers on movement speed and its cost
+    pub const SENSE_TERRAIN:                           bool = false;                // beings feel the terrain of the cell they stand in
+    pub const SENSE_POPULATION:                        bool = false;                // beings feel the world's head count, relative to B_START_COUNT
+    pub const PHEROMONES:                              bool = false;                // beings get an extra output that marks their cell, and sense the marks
+    pub const PHEROMONE_DEPOSIT:                        f32 = 1.;                   // laid per step at full output strength
+    pub const PHEROMONE_DECAY:                          f32 = 0.98;                 // every cell's pheromone is scaled by this once a step
 
     pub const N_FOOD_SPAWN_PER_STEP:                  usize = 1;
+    pub const FOOD_GOES_TO_NEAREST:                    bool = false;                // contested food goes to the closest being rather than the first processed
+    pub const MIN_FOOD_SEPARATION:                      f32 = 0.;                   // plants aren't spawned closer than this to other food; 0. allows stacking
+    pub const FOOD_SPAWN_RETRIES:                     usize = 10;                  // resamples for a plant whose spot is turned down before it's skipped
+    pub const FOOD_SPAWN_AVOIDANCE:                     f32 = 0.;                   // chance, per being in its cell, that a plant's spot is resampled
     
-    pub static mut MAX_FOOD:                          usize = 500;
+    pub const MAX_FOOD:                               usize = 500;                  // a world's starting food cap, unless the first stage sets one
     pub const MIN_FOOD:                               usize = 125;
+    pub const FOOD_CAP_MODE:                    FoodCapMode = FoodCapMode::Plants;  // what counts against MAX_FOOD when plants regrow
+    pub const MAX_FLESH_FOOD:                 Option<usize> = None;                 // scattering stops short once this much flesh lies about
     pub const MAX_FOOD_REDUCTION:                     usize = 5;
 
+    pub const EMPTY_INPUT_POLICY:          EmptyInputPolicy = EmptyInputPolicy::Sentinel;
+    pub const WALL_SENSE:                         WallSense = WallSense::Borders;  // Borders keeps the four channels older archives were trained on
+    pub const VISION_MEMORY_DECAY:              Option<f32> = None;                 // remembered sightings fade by this factor per unseen step
+    pub const SPEECHLET_RETENTION:                    usize = 0;                    // steps the last speechlet heard is replayed, fading, while nothing new is
+    pub const SENSE_DAMAGE:                            bool = false;                // beings feel the collision damage they took last step
+    pub const CROSSOVER_GRANULARITY:   CrossoverGranularity = CrossoverGranularity::Weight;
+    pub const MUTATION_PROFILE:             MutationProfile = MutationProfile::UNIFORM; // per-branch multipliers on the mutation rate
+    pub const SENSES:                                Senses = Senses::ALL;          // branches to switch off for ablations, e.g. speechlet: false for deaf beings
+    pub const ALLOW_SELF_CROSSOVER:                    bool = false;                // a parent may be drawn twice for the same child
+    pub const OFFSPRING_MUTATION_RATE:          Option<f32> = None;                 // bred offspring are mutated at this rather than the stage's rate; Some(0.) breeds them unmutated
+    pub const CONTINUOUS_SELECTION:                    bool = false;                // survivors are drawn as parents in proportion to their energy, not uniformly
+    pub const REPRODUCTION_ORDER:    DeathReproductionOrder = DeathReproductionOrder::DeathFirst; // whether beings dying as a generation ends may still parent the next
+    pub const CATASTROPHE_STRENGTH:                     f32 = 0.5;                  // mutation rate of a catastrophe, applied once to every founder
+    pub const CATASTROPHE_INTERVAL:           Option<usize> = None;                 // a catastrophe strikes every this many generations
+    pub const CATASTROPHE_DIVERSITY_FLOOR:      Option<f32> = None;                 // ...or whenever the founders' weight_diversity falls under this
+    pub const LSTM_STATE_DECAY:                         f32 = 1.;                   // recurrent state is scaled by this before every forward; 1. never forgets
+    pub const MERGE_FLIP_RATE:                          f32 = 0.;                   // chance a SumFxModel's mutation flips it between concat and add mode
+    pub const MHA_USE_ATTENTION:                       bool = true;                 // false ablates MhaModel's attention, leaving a SumFxModel with dormant mha weights
+    pub const MASK_SENTINEL_IN_MEAN:                   bool = true;                 // leave the `-1.` padding row out of non-empty sensory means
+    pub const POOLING:                              Pooling = Pooling::Mean;        // how every branch reduces its sensory set to one row
+
     pub const SPEECHLET_LEN:                          usize = 8;                   // length of the sound vector a being can emit
-    pub const B_OUTPUT_LEN:                           usize = 4 + SPEECHLET_LEN;   // (f-b, rotate, spawn obstruct, spawn_speechlet, *speechlet)
-    
-    pub type BACKEND                                        = backend::NdArray;
+    pub const PHEROMONE_OUTPUT:                       usize = 4 + SPEECHLET_LEN;   // index of the deposit output, if PHEROMONES
+    pub const MINE_OUTPUT:                            usize = PHEROMONE_OUTPUT + PHEROMONES as usize; // index of the mine output, if MINING
+    pub const RESIZE_OUTPUT:                          usize = MINE_OUTPUT + MINING as usize; // index of the resize output, if RESIZING
+    pub const B_OUTPUT_LEN:                           usize = RESIZE_OUTPUT + RESIZING as usize; // (f-b, rotate, spawn obstruct, spawn_speechlet, *speechlet, [deposit], [mine], [resize])
+
     pub const DEVICE:       backend::ndarray::NdArrayDevice = backend::ndarray::NdArrayDevice::Cpu;
-    pub type Model                                          = SumFxModel<BACKEND>;
+    }
 }
 
 use consts::*;
 
+// what a being's sensory branch makes of seeing nothing at all
+#[derive(Debug, Clone, Copy)]
+pub enum EmptyInputPolicy {
+    Sentinel, // pool over the lone `-1.` sentinel row, as if it were an entity
+    Zeros,    // an empty set pools to zeros
+    Flag,     // as Zeros, and the self input carries one "saw nothing" flag per sensory branch
+}
+
+// how a being feels the world's edges in its own row
+#[derive(Debug, Clone, Copy)]
+pub enum WallSense {
+    Borders, // is_border_in_sight's per-axis distances and angles
+    Nearest, // nearest_wall's distance and bearing
+}
+
+impl WallSense {
+    pub fn channels(&self) -> &'static [&'static str] {
+        match self {
+            WallSense::Borders => &[
+                "x_border_distance",
+                "x_border_angle",
+                "y_border_distance",
+                "y_border_angle",
+            ],
+            WallSense::Nearest => &["wall_distance", "wall_bearing"],
+        }
+    }
+
+    pub fn sense(&self, pos: Vec2, rot: f32, bounds: (Vec2, Vec2)) -> Vec<f32> {
+        match self {
+            WallSense::Borders => is_border_in_sight(pos, rot, bounds).to_vec(),
+            WallSense::Nearest => nearest_wall(pos, rot, bounds).to_vec(),
+        }
+    }
+}
+
+// how beings are rotated when the world is (re)populated
+#[derive(Debug, Clone, Copy)]
+pub enum InitialFacing {
+    Uniform,
+    Aligned(f32),                           // everyone faces this angle
+    Clustered { centre: f32, spread: f32 }, // uniformly within `spread` either side of `centre`
+}
+
+impl InitialFacing {
+    pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+        match *self {
+            InitialFacing::Uniform => rng.gen_range(-PI..PI),
+            InitialFacing::Aligned(theta) => theta,
+            InitialFacing::Clustered { centre, spread } => centre + rng.gen_range(-spread..=spread),
+        }
+    }
+}
+
+// where beings are placed when the world is (re)populated
+#[derive(Debug, Clone, Copy)]
+pub enum BeingSpawn {
+    Uniform,
+    Clusters { n: usize, radius: f32 }, // round-robin around `n` random centres, each within `radius` of its own
+}
+
+impl BeingSpawn {
+    pub fn positions(&self, count: usize, bounds: (Vec2, Vec2), rng: &mut impl Rng) -> Vec<Vec2> {
+        let mut in_world = || in_bounds(bounds, B_RADIUS, rng);
+
+        match *self {
+            BeingSpawn::Uniform => (0..count).map(|_| in_world()).collect(),
+            BeingSpawn::Clusters { n, radius } => {
+                let centres: Vec<Vec2> = (0..n.max(1)).map(|_| in_world()).collect();
+                let mut placed: Vec<Vec2> = Vec::with_capacity(count);
+                for i in 0..count {
+                    let centre = centres[i % centres.len()];
+                    // a few draws to find a spot clear of earlier founders; a cluster too tight to fit everyone
+                    // keeps the last draw and leaves the overlap to check_collisions.
+                    // clamping moves a point no further from a centre that's itself in bounds
+                    let mut pos = centre;
+                    for _ in 0..16 {
+                        pos = scatter_near(centre, radius, bounds, rng);
+                        if placed.iter().all(|p| p.distance(pos) >= 2. * B_RADIUS) {
+                            break;
+                        }
+                    }
+                    placed.push(pos);
+                }
+                placed
+            }
+        }
+    }
+}
+
+// the weight_diversity of a set of models; parameters are compared position by position
+fn model_diversity<'a>(models: impl Iterator<Item = &'a Model>) -> f32 {
+    let params: Vec<Vec<f32>> = models.map(|m| m.params()).collect();
+    weight_diversity(&params)
+}
+
+// a uniform point in the disc of `radius` around `centre`, clamped to where a being fits in the arena
+fn scatter_near(centre: Vec2, radius: f32, (min, max): (Vec2, Vec2), rng: &mut impl Rng) -> Vec2 {
+    let (theta, dist) = (rng.gen_range(-PI..PI), radius * rng.gen::<f32>().sqrt());
+    (centre + Vec2::from_angle(theta) * dist).clamp(min + B_RADIUS, max - B_RADIUS)
+}
+
+// whether the per-entity passes may spread over threads. every such pass is a per-entity update followed by
+// a collect in slotmap order, so both give the same results; Serial is for debugging without threads at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parallelism {
+    Serial,
+    Rayon, // as Serial without the `parallel` feature
+}
+
+// what becomes of a being when it dies. its remains are worth C_START_ENERGY, plus any energy it still had
+// if it died of its wounds
+#[derive(Debug, Clone, Copy)]
+pub enum DeathRemains {
+    Flesh,    // B_SCATTER_COUNT bits of flesh food around where it died
+    Corpse,   // one corpse to feed on
+    Absorbed, // DEATH_ABSORPTION_FRACTION of it goes straight to the nearest being in sight, the rest is lost
+}
+
+// whether reworld breeds from the beings that died in the step it ends the generation on
+#[derive(Debug, Clone, Copy)]
+pub enum DeathReproductionOrder {
+    DeathFirst,        // the dead are gone before breeding; only the living become parents
+    ReproductionFirst, // a being dying in that step can still leave offspring
+}
+
+// which food repop_foods counts against MAX_FOOD
+#[derive(Debug, Clone, Copy)]
+pub enum FoodCapMode {
+    Plants, // flesh is left out, so plants regrow however much flesh a die-off scattered
+    All,    // flesh takes up room that plants would otherwise regrow into
+}
+
+// what a plant is worth when it spawns. food is seen at its value over the distribution's max
+#[derive(Debug, Clone, Copy)]
+pub enum FoodValueDist {
+    Fixed(f32),
+    Uniform { min: f32, max: f32 },
+    Bimodal { low: f32, high: f32, p_high: f32 }, // `high` with probability p_high, else `low`
+}
+
+impl FoodValueDist {
+    pub fn sample(&self, rng: &mut impl Rng) -> f32 {
+        match *self {
+            FoodValueDist::Fixed(val) => val,
+            FoodValueDist::Uniform { min, max } => rng.gen_range(min..=max),
+            FoodValueDist::Bimodal { low, high, p_high } => {
+                if rng.gen::<f32>() < p_high {
+                    high
+                } else {
+                    low
+                }
+            }
+        }
+    }
+
+    pub fn max(&self) -> f32 {
+        match *self {
+            FoodValueDist::Fixed(val) => val,
+            FoodValueDist::Uniform { max, .. } => max,
+            FoodValueDist::Bimodal { low, high, .. } => low.max(high),
+        }
+    }
+}
+
+// where a spawned obstruct ends up relative to where its being asked for it
+#[derive(Debug, Clone, Copy)]
+pub enum ObstructPlacement {
+    Free,
+    Grid { cell: f32 }, // at the nearest node of a `cell`-spaced grid, and only if no obstruct is already there
+}
+
+impl ObstructPlacement {
+    pub fn snap(&self, pos: Vec2) -> Vec2 {
+        match *self {
+            ObstructPlacement::Free => pos,
+            ObstructPlacement::Grid { cell } => (pos / cell).round() * cell,
+        }
+    }
+}
+
+// how a being's age bears on its upkeep and appearance
+#[derive(Debug, Clone, Copy)]
+pub enum AgingModel {
+    None,
+    Senescence { rate: f32 }, // tire rate grows by `rate` of itself per step lived
+}
+
+impl AgingModel {
+    pub fn tire_rate(&self, age: usize) -> f32 {
+        match self {
+            AgingModel::None => B_TIRE_RATE,
+            AgingModel::Senescence { rate } => B_TIRE_RATE * (1. + rate * age as f32),
+        }
+    }
+
+    // 0. for the newborn, 1. for the decrepit
+    pub fn tint(&self, age: usize) -> f32 {
+        match self {
+            AgingModel::None => 0.,
+            AgingModel::Senescence { .. } => (age as f32 / B_AGE_TINT_SPAN).min(1.),
+        }
+    }
+}
+
+// one leg of a curriculum: for these generations, reworld at this threshold and breed at this mutation rate.
+// the food allowance is reset to max_food as the stage begins, and shrinks from there as usual
+#[derive(Debug, Clone)]
+pub struct Stage {
+    pub generations: std::ops::Range<usize>,
+    pub reworld_threshold: usize,
+    pub max_food: usize,
+    pub mutation_rate: f32,
+}
+
+// the stage of `stages` (STAGES) covering a generation. generations past the last stage stay in it
+pub fn stage_for(stages: &[Stage], generation: usize) -> Option<&Stage> {
+    stages
+        .iter()
+        .find(|stage| stage.generations.contains(&generation))
+        .or(stages.last())
+}
+
+// stages must run back to back from generation 0, without gaps or overlaps
+pub fn stages_are_contiguous(stages: &[Stage]) -> bool {
+    let mut next = 0;
+    for stage in stages {
+        if stage.generations.start != next || stage.generations.is_empty() {
+            return false;
+        }
+        next = stage.generations.end;
+    }
+    true
+}
+
+// a wall-clock target for a step's physics and thinking. a step that overruns it costs the world a quality
+// level, one that takes under half of it wins one back. at level n every sensory set is cut to its first
+// full_rows >> n rows (at least one) before the being thinks, which shrinks the forward passes; the
+// collision pass itself is untouched. without a budget the quality never changes
+#[derive(Debug, Clone, Copy)]
+pub struct TimeBudget {
+    pub step: Duration,
+    pub full_rows: usize,
+    pub max_level: u32,
+}
+
+// how the ground in one partition cell bears on the beings crossing it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerrainCell {
+    pub speed: f32, // multiplies movement
+    pub tire: f32,  // multiplies the energy movement costs
+}
+
+impl TerrainCell {
+    pub const PLAIN: Self = TerrainCell {
+        speed: 1.,
+        tire: 1.,
+    };
+}
+
+// how the terrain grid is laid out when a world is created
+#[derive(Debug, Clone, Copy)]
+pub enum TerrainLayout {
+    Uniform, // plain everywhere
+    Patches {
+        count: usize,
+        radius: usize, // in cells
+        cell: TerrainCell,
+    }, // discs of `cell` at random over plain ground
+    File(&'static str), // plain but for lines of `i j speed tire`
+}
+
+impl TerrainLayout {
+    // a TerrainCell per partition cell, indexed like the cell indices
+    pub fn build(&self, rng: &mut impl Rng) -> Vec<TerrainCell> {
+        let mut terrain = vec![TerrainCell::PLAIN; (N_CELLS + 1).pow(2)];
+
+        match *self {
+            TerrainLayout::Uniform => {}
+            TerrainLayout::Patches {
+                count,
+                radius,
+                cell,
+            } => {
+                for _ in 0..count {
+                    let (ci, cj) = (rng.gen_range(0..N_CELLS), rng.gen_range(0..N_CELLS));
+                    for i in ci.saturating_sub(radius)..=(ci + radius).min(N_CELLS) {
+                        for j in cj.saturating_sub(radius)..=(cj + radius).min(N_CELLS) {
+                            if i.abs_diff(ci).pow(2) + j.abs_diff(cj).pow(2) <= radius.pow(2) {
+                                terrain[two_to_one((i, j))] = cell;
+                            }
+                        }
+                    }
+                }
+            }
+            TerrainLayout::File(path) => {
+                let text = std::fs::read_to_string(path)
+                    .unwrap_or_else(|e| panic!("could not read terrain {}: {}", path, e));
+                for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                    let fields: Vec<&str> = line.split_whitespace().collect();
+                    let parsed = match fields[..] {
+                        [i, j, speed, tire] => i
+                            .parse::<usize>()
+                            .ok()
+                            .zip(j.parse::<usize>().ok())
+                            .zip(speed.parse::<f32>().ok().zip(tire.parse::<f32>().ok())),
+                        _ => None,
+                    };
+                    let Some(((i, j), (speed, tire))) =
+                        parsed.filter(|((i, j), _)| *i <= N_CELLS && *j <= N_CELLS)
+                    else {
+                        panic!("bad terrain line in {}: {}", path, line);
+                    };
+                    terrain[two_to_one((i, j))] = TerrainCell { speed, tire };
+                }
+            }
+        }
+
+        terrain
+    }
+}
+
+// ids restart every generation, so the generation goes into a being's seed too; otherwise the nth being of
+// every generation would draw the same stream
+fn being_seed(root: u64, generation: usize, id: BeingId) -> u64 {
+    splitmix64(splitmix64(root ^ generation as u64) ^ u64::from(id))
+}
+
+// a fixed mix, unlike std's hashers, so seeds stay put across Rust releases
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
 // maps 2D space-partition index to 1D Vec index
 fn two_to_one((i, j): (usize, usize)) -> usize {
     i * N_CELLS + j
@@ -119,29 +618,52 @@ pub fn pos_to_cell(pos: Vec2) -> (usize, usize) {
     (i, j)
 }
 
-pub fn lef_border_trespass(i: f32, r: f32) -> bool {
-    i - r <= 1.
+// the (min, max) corners of where beings and food are kept: the ARENA if there is one, else the whole world
+pub fn arena_bounds() -> (Vec2, Vec2) {
+    bounds_of(ARENA)
+}
+
+pub fn bounds_of(arena: Option<Rect>) -> (Vec2, Vec2) {
+    match arena {
+        Some(arena) => (
+            arena.point().into(),
+            Vec2::new(arena.right(), arena.bottom()),
+        ),
+        None => (Vec2::ZERO, Vec2::splat(W_FLOAT)),
+    }
+}
+
+// a uniform point at least `margin` inside the walls of `bounds`
+fn in_bounds((min, max): (Vec2, Vec2), margin: f32, rng: &mut impl Rng) -> Vec2 {
+    Vec2::new(
+        rng.gen_range(min.x + margin..max.x - margin),
+        rng.gen_range(min.y + margin..max.y - margin),
+    )
+}
+
+pub fn lef_border_trespass(i: f32, r: f32, (min, _): (Vec2, Vec2)) -> bool {
+    i - r <= min.x + 1.
 }
 
-pub fn rig_border_trespass(i: f32, r: f32) -> bool {
-    i + r >= W_FLOAT - 1.
+pub fn rig_border_trespass(i: f32, r: f32, (_, max): (Vec2, Vec2)) -> bool {
+    i + r >= max.x - 1.
 }
 
-pub fn top_border_trespass(j: f32, r: f32) -> bool {
-    j - r <= 1.
+pub fn top_border_trespass(j: f32, r: f32, (min, _): (Vec2, Vec2)) -> bool {
+    j - r <= min.y + 1.
 }
 
-pub fn bot_border_trespass(j: f32, r: f32) -> bool {
-    j + r >= W_FLOAT - 1.
+pub fn bot_border_trespass(j: f32, r: f32, (_, max): (Vec2, Vec2)) -> bool {
+    j + r >= max.y - 1.
 }
 
 // out of bounds
-pub fn oob(xy: Vec2, r: f32) -> bool {
+pub fn oob_within(xy: Vec2, r: f32, bounds: (Vec2, Vec2)) -> bool {
     let (x, y) = (xy[0], xy[1]);
-    lef_border_trespass(x, r)
-        || rig_border_trespass(x, r)
-        || top_border_trespass(y, r)
-        || bot_border_trespass(y, r)
+    lef_border_trespass(x, r, bounds)
+        || rig_border_trespass(x, r, bounds)
+        || top_border_trespass(y, r, bounds)
+        || bot_border_trespass(y, r, bounds)
 }
 
 pub fn b_collides_b(b1: &Being, b2: &Being) -> (f32, f32, Vec2, [f32; 3 + GENOME_LEN]) {
@@ -195,12 +717,48 @@ pub fn b_collides_f(b: &Being, f: &Food) -> (f32, [f32; 5]) {
             1.,
             centre_dist / B_FOV_PX,
             b.pos.angle_between(f.pos) / PI,
-            f.val / F_VAL,
+            f.val / F_VALUE_DIST.max(),
             f.age / F_START_AGE,
         ],
     )
 }
 
+// takes an obstruct's age down at `strength` of the mine output and returns the energy that's worth.
+// age_obstructs clears out whatever is mined down to nothing
+fn mine(o: &mut Obstruct, strength: f32, substeps: f32) -> f32 {
+    let mined = (strength.min(1.) * MINE_RATE / substeps).min(o.age);
+    o.age -= mined;
+    mined * MINE_YIELD
+}
+
+// a corpse is seen as food, its strength what's left of it
+pub fn b_collides_c(b: &Being, c: &Corpse) -> (f32, [f32; 5]) {
+    let centre_dist = b.pos.distance(c.pos);
+    let (r1, r2) = (b.radius, C_RADIUS);
+    (
+        r1 + r2 - centre_dist,
+        [
+            1.,
+            centre_dist / B_FOV_PX,
+            b.pos.angle_between(c.pos) / PI,
+            c.energy / F_VALUE_DIST.max(),
+            c.energy / C_START_ENERGY,
+        ],
+    )
+}
+
+// a step's damage to a being struck at `axis_alignment` (its facing dotted with the way to its striker), worse
+// from behind. `speed_weight` (COMBAT_SPEED_WEIGHT) of it scales with the striker's per-step speed
+pub fn collision_damage(axis_alignment: f32, striker_speed: f32, speed_weight: f32) -> f32 {
+    let speed_factor = 1. - speed_weight + speed_weight * striker_speed.min(1.);
+    let damage = if axis_alignment > 0. {
+        B_HEADON_DAMAGE * axis_alignment
+    } else {
+        B_REAR_DAMAGE * axis_alignment.abs()
+    };
+    damage * speed_factor
+}
+
 pub fn b_collides_s(b: &Being, s: &Speechlet) -> f32 {
     let c1c2 = s.pos - b.pos;
     let centre_dist = c1c2.length();
@@ -209,22 +767,296 @@ pub fn b_collides_s(b: &Being, s: &Speechlet) -> f32 {
     r1 + r2 - centre_dist
 }
 
-pub fn is_border_in_sight(pos: Vec2, rot: f32) -> [f32; 4] {
+// the indices of two of n survivors to cross, drawn from the world's rng. a lone survivor has no one else to
+// cross with, so it's paired with itself whether or not that's allowed
+pub fn choose_parents(rng: &mut StdRng, n: usize, allow_self: bool) -> (usize, usize) {
+    if allow_self || n < 2 {
+        (rng.gen_range(0..n), rng.gen_range(0..n))
+    } else {
+        let parents = index::sample(rng, n, 2);
+        (parents.index(0), parents.index(1))
+    }
+}
+
+// as choose_parents, but each is drawn in proportion to its fitness. should nobody left have any, the draw
+// falls back to a uniform one
+pub fn choose_fit_parents(rng: &mut StdRng, fitness: &[f32], allow_self: bool) -> (usize, usize) {
+    let n = fitness.len();
+    let mut pick = |skip: Option<usize>| {
+        let weights = (0..n).map(|i| {
+            if Some(i) == skip {
+                0.
+            } else {
+                fitness[i].max(0.)
+            }
+        });
+        match WeightedIndex::new(weights) {
+            Ok(dist) => dist.sample(rng),
+            Err(_) => (0..n).filter(|&i| Some(i) != skip).choose(rng).unwrap_or(0),
+        }
+    };
+    let first = pick(None);
+    let second = pick((!allow_self && n >= 2).then_some(first));
+    (first, second)
+}
+
+// a child of two of `parents`, drawn by fitness if given, and the index of the first. the parents are cloned
+// before crossover and mutate, which consume the model they're called on, so breeding never touches their
+// own networks
+fn breed(
+    rng: &mut StdRng,
+    parents: &[Model],
+    fitness: Option<&[f32]>,
+    mutation_rate: f32,
+) -> (Model, usize) {
+    let (i1, i2) = match fitness {
+        Some(fitness) => choose_fit_parents(rng, fitness, ALLOW_SELF_CROSSOVER),
+        None => choose_parents(rng, parents.len(), ALLOW_SELF_CROSSOVER),
+    };
+    let child = parents[i1]
+        .clone()
+        .crossover(parents[i2].clone(), 0.05, &DEVICE)
+        .mutate(mutation_rate, &DEVICE);
+    (child, i1)
+}
+
+// stakes a being's claim to a food at `dist`, which stands only if no other being is nearer
+pub fn claim_food(
+    claims: &mut BTreeMap<DefaultKey, (DefaultKey, f32)>,
+    food: DefaultKey,
+    being: DefaultKey,
+    dist: f32,
+) {
+    let claim = claims.entry(food).or_insert((being, dist));
+    if dist < claim.1 {
+        *claim = (being, dist);
+    }
+}
+
+// earliest fraction t of the move p0 -> p0 + d at which the mover's centre comes within `reach` of c, if any
+pub fn swept_circle_hit(p0: Vec2, d: Vec2, c: Vec2, reach: f32) -> Option<f32> {
+    let pc = p0 - c;
+    let (a, b, cc) = (d.dot(d), 2. * d.dot(pc), pc.dot(pc) - reach.powi(2));
+
+    if cc <= 0. {
+        return Some(0.);
+    }
+    if a == 0. {
+        return None;
+    }
+
+    let disc = b.powi(2) - 4. * a * cc;
+    if disc < 0. {
+        return None;
+    }
+
+    let t = (-b - disc.sqrt()) / (2. * a);
+    if (0. ..=1.).contains(&t) {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+// applies `update` to every entity, across threads under the `parallel` feature unless `parallelism` says
+// otherwise, and returns the key and position of those it reports dead, in slotmap order either way
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+pub fn scan_values<T: Send>(
+    parallelism: Parallelism,
+    map: &mut SlotMap<DefaultKey, T>,
+    update: impl Fn(&mut T) -> Option<Vec2> + Sync + Send,
+) -> Vec<(DefaultKey, Vec2)> {
+    #[cfg(feature = "parallel")]
+    if parallelism == Parallelism::Rayon {
+        let mut entries: Vec<(DefaultKey, &mut T)> = map.iter_mut().collect();
+        return entries
+            .par_iter_mut()
+            .filter_map(|(k, v)| update(&mut **v).map(|pos| (*k, pos)))
+            .collect();
+    }
+
+    map.iter_mut()
+        .filter_map(|(k, v)| update(v).map(|pos| (k, pos)))
+        .collect()
+}
+
+// the first three components of a speechlet as rgb; speech comes out of a tanh, so -1. is none of a channel
+pub fn speechlet_color(speechlet: &[f32; SPEECHLET_LEN]) -> Color {
+    let channel = |c: usize| 0.5 + 0.5 * speechlet.get(c).copied().unwrap_or(0.);
+    Color::new(channel(0), channel(1), channel(2), 1.)
+}
+
+// a fixed projection of a genome onto three axes, squashed into rgb. pure and stable, so tools outside the
+// sim can reproduce it; nearby genomes get nearby colours
+pub fn genome_color(genome: &[f32; GENOME_LEN]) -> Color {
+    let channel = |c: usize| {
+        let projection: f32 = genome
+            .iter()
+            .enumerate()
+            .map(|(i, gene)| gene * ((3 * i + c) as f32).cos())
+            .sum();
+        0.5 + 0.5 * (projection / (GENOME_LEN as f32).sqrt()).tanh()
+    };
+
+    Color::new(channel(0), channel(1), channel(2), 1.)
+}
+
+// whether any obstruct lies across the straight line from `from` to `to`. only the cells around the line's
+// bounding box are searched, widened by one for obstructs that overhang their cell
+pub fn line_blocked(
+    obstructs: &SlotMap<DefaultKey, Obstruct>,
+    obstruct_cells: &CellIndex,
+    from: Vec2,
+    to: Vec2,
+) -> bool {
+    let ((i0, j0), (i1, j1)) = (pos_to_cell(from.min(to)), pos_to_cell(from.max(to)));
+
+    (i0.saturating_sub(1)..=(i1 + 1).min(N_CELLS)).any(|i| {
+        (j0.saturating_sub(1)..=(j1 + 1).min(N_CELLS)).any(|j| {
+            obstruct_cells
+                .cell(two_to_one((i, j)))
+                .iter()
+                .any(|k| swept_circle_hit(from, to - from, obstructs[*k].pos, O_RADIUS).is_some())
+        })
+    })
+}
+
+// whether `target` lies within the `arc` (REAR_BLIND_ARC) behind a being at `pos` facing `rotation`
+pub fn in_blind_spot(pos: Vec2, rotation: f32, target: Vec2, arc: f32) -> bool {
+    let to_target = target - pos;
+    arc > 0.
+        && to_target != Vec2::ZERO
+        && dir_from_theta(rotation).angle_between(to_target).abs() > PI - arc / 2.
+}
+
+// 1. dead ahead, falling off to 1. - `strength` (FOVEATION_STRENGTH) dead behind
+pub fn foveal_weight(pos: Vec2, rotation: f32, target: Vec2, strength: f32) -> f32 {
+    let alignment = dir_from_theta(rotation).dot((target - pos).normalize_or_zero());
+    1. - strength * (1. - alignment) / 2.
+}
+
+pub fn foveate(rows: &mut [Vec<f32>], weights: &[f32]) {
+    for (row, weight) in rows.iter_mut().zip(weights) {
+        row.iter_mut().for_each(|x| *x *= weight);
+    }
+}
+
+// the pheromone in a being's cell, then the gradient across the neighbouring cells turned into its frame:
+// ahead, then to its left
+fn pheromone_sense(pheromone: &[f32], (i, j): (usize, usize), rotation: f32) -> [f32; 3] {
+    let at = |i: usize, j: usize| pheromone[two_to_one((i.min(N_CELLS), j.min(N_CELLS)))];
+    let gradient = Vec2::new(
+        at(i + 1, j) - at(i.saturating_sub(1), j),
+        at(i, j + 1) - at(i, j.saturating_sub(1)),
+    ) / 2.;
+    let heading = dir_from_theta(rotation);
+    [at(i, j), gradient.dot(heading), heading.perp_dot(gradient)]
+}
+
+// the being's own row, in SensorySchema::standard's order
+fn own_row(
+    b: &Being,
+    empty_flags: [f32; 3],
+    terrain: &[TerrainCell],
+    pheromone: &[f32],
+    crowding: f32,
+    bounds: (Vec2, Vec2),
+) -> Vec<f32> {
+    let mut self_vec = WALL_SENSE.sense(b.pos, b.rotation, bounds);
+    self_vec.extend([b.energy / B_START_ENERGY]);
+    if let EmptyInputPolicy::Flag = EMPTY_INPUT_POLICY {
+        self_vec.extend(empty_flags);
+    }
+    if VISION_MEMORY_DECAY.is_some() {
+        self_vec.extend(b.vision_memory);
+    }
+    if SENSE_DAMAGE {
+        self_vec.extend([b.damage_taken / B_START_ENERGY]);
+    }
+    if SEPARATE_HEALTH {
+        self_vec.extend([b.health / B_START_HEALTH]);
+    }
+    if SENSE_TERRAIN {
+        let ground = terrain[two_to_one(b.cell)];
+        self_vec.extend([ground.speed, ground.tire]);
+    }
+    if PHEROMONES {
+        self_vec.extend(pheromone_sense(pheromone, b.cell, b.rotation));
+    }
+    if SENSE_POPULATION {
+        self_vec.push(crowding);
+    }
+    self_vec
+}
+
+// appends each set's `-1.` sentinel row and runs the model on the lot; the rows are left for the caller to clear
+fn forward_rows(
+    model: &mut Model,
+    being_rows: &mut Vec<Vec<f32>>,
+    fo_rows: &mut Vec<Vec<f32>>,
+    speechlet_rows: &mut Vec<Vec<f32>>,
+    own_row: &[f32],
+    [being_width, fo_width, speechlet_width, own_width]: [usize; 4],
+) -> [f32; B_OUTPUT_LEN] {
+    let tensorize_set = |rows: &mut Vec<Vec<f32>>, width: usize| {
+        rows.push(vec![-1.; width]);
+        tensorize_2dvec(rows, [rows.len(), width], &DEVICE).no_grad()
+    };
+    let being_tensor = tensorize_set(being_rows, being_width);
+    let fo_tensor = tensorize_set(fo_rows, fo_width);
+    let speechlet_tensor = tensorize_set(speechlet_rows, speechlet_width);
+    let self_tensor = Tensor::<BACKEND, 1>::from_floats(own_row, &DEVICE)
+        .reshape([1, own_width])
+        .no_grad();
+
+    let model_output = model
+        .forward(being_tensor, fo_tensor, speechlet_tensor, self_tensor)
+        .into_data()
+        .value;
+
+    let mut output = [0.; B_OUTPUT_LEN];
+    (0..B_OUTPUT_LEN).into_iter().for_each(|i| {
+        output[i] = model_output[i];
+    });
+    output
+}
+
+// the distance to the nearest wall over B_FOV_PX, capped at 1., and its bearing off the being's heading over PI,
+// positive anticlockwise. in a corner the tie goes to the x walls, left before right
+pub fn nearest_wall(pos: Vec2, rot: f32, (min, max): (Vec2, Vec2)) -> [f32; 2] {
+    let walls = [
+        (pos.x - min.x, Vec2::NEG_X),
+        (max.x - pos.x, Vec2::X),
+        (pos.y - min.y, Vec2::NEG_Y),
+        (max.y - pos.y, Vec2::Y),
+    ];
+    let (dist, normal) =
+        walls.into_iter().fold(
+            walls[0],
+            |nearest, wall| if wall.0 < nearest.0 { wall } else { nearest },
+        );
+
+    [
+        (dist / B_FOV_PX).min(1.),
+        dir_from_theta(rot).angle_between(normal) / PI,
+    ]
+}
+
+pub fn is_border_in_sight(pos: Vec2, rot: f32, (min, max): (Vec2, Vec2)) -> [f32; 4] {
     let (x, y) = (pos.x, pos.y);
     let mut rel_vec: [f32; 4] = [1., 0., 1., 0.];
-    let w = W_SIZE as f32;
-    if x + B_FOV_PX > w {
-        rel_vec[0] = (w - x) / B_FOV_PX;
+    if x + B_FOV_PX > max.x {
+        rel_vec[0] = (max.x - x) / B_FOV_PX;
         rel_vec[1] = rot + 0.5;
-    } else if x - B_FOV_PX < 0. {
-        rel_vec[0] = x / B_FOV_PX;
+    } else if x - B_FOV_PX < min.x {
+        rel_vec[0] = (x - min.x) / B_FOV_PX;
         rel_vec[1] = rot - 0.5;
     }
-    if y + B_FOV_PX > w {
-        rel_vec[2] = (w - y) / B_FOV_PX;
+    if y + B_FOV_PX > max.y {
+        rel_vec[2] = (max.y - y) / B_FOV_PX;
         rel_vec[3] = rot + 1.;
-    } else if y - B_FOV_PX < 0. {
-        rel_vec[2] = y / B_FOV_PX;
+    } else if y - B_FOV_PX < min.y {
+        rel_vec[2] = (y - min.y) / B_FOV_PX;
         rel_vec[3] = rot;
     }
 
@@ -237,26 +1069,163 @@ pub struct Being {
     radius: f32,
     rotation: f32,
     energy: f32,
+    health: f32, // only ever lowered if SEPARATE_HEALTH
     genome: [f32; GENOME_LEN],
+    age: usize,
+    species: usize,
+    grace_remaining: usize, // steps left in which collisions don't hurt
+
+    // physically collides with whatever's layer overlaps its mask; perception ignores both
+    collision_layer: u32,
+    collision_mask: u32,
 
     cell: (usize, usize),
-    id: usize,
+    id: BeingId,
 
     pos_update: Vec2,
-    energy_update: f32,
+    energy_update: f32, // every energy delta of a step lands here first, see World::step
+    damage_taken: f32,  // collision damage since the being last perceived, a share of energy_update
+    health_update: f32, // settled alongside energy_update
     rotation_update: f32,
+    rotated: f32, // turned so far this step, over all its substeps; capped at B_MAX_ROT_PER_STEP
 
     being_inputs: Vec<Vec<f32>>,
     food_obstruct_inputs: Vec<Vec<f32>>,
     speechlet_inputs: Vec<Vec<f32>>,
+    // a foveal_weight per row of the inputs above, in the same order
+    being_weights: Vec<f32>,
+    food_obstruct_weights: Vec<f32>,
+    speechlet_weights: Vec<f32>,
+
+    // (angle, distance, strength) of the nearest food, then of the nearest being, last seen
+    vision_memory: [f32; 6],
+    last_heard: Option<(Vec<f32>, usize)>, // the last speechlet row heard and the steps since, see replay_last_heard
+    visited_cells: VecDeque<(usize, usize)>, // least recently entered first, at most EXPLORATION_MEMORY long
+    history: VecDeque<Vec2>, // oldest first, a position every TRAJECTORY_EVERY steps, at most TRAJECTORY_LEN long
+    rng: Option<StdRng>, // for the being's own stochasticity, independent of the order beings are processed in
 
     output: [f32; B_OUTPUT_LEN],
 }
 
+impl Being {
+    pub fn genome_color(&self) -> Color {
+        genome_color(&self.genome)
+    }
+
+    // collision damage is taken out of health when it's kept apart, and out of energy otherwise
+    fn take_damage(&mut self, damage: f32) {
+        self.take_damage_under(damage, SEPARATE_HEALTH);
+    }
+
+    fn take_damage_under(&mut self, damage: f32, separate_health: bool) {
+        if separate_health {
+            self.health_update -= damage;
+        } else {
+            self.energy_update -= damage;
+        }
+    }
+
+    // a cell missing from the recent ones pays `bonus` (EXPLORATION_BONUS); either way it becomes the most recent
+    fn enter_cell(&mut self, cell: (usize, usize), bonus: f32) {
+        match self.visited_cells.iter().position(|c| *c == cell) {
+            Some(n) => {
+                self.visited_cells.remove(n);
+            }
+            None => self.energy_update += bonus,
+        }
+
+        self.visited_cells.push_back(cell);
+        if self.visited_cells.len() > EXPLORATION_MEMORY {
+            self.visited_cells.pop_front();
+        }
+    }
+
+    // attention isn't free: `per_entity` (B_PERCEPTION_COST) for every row perceived this step
+    fn perception_cost(&self, per_entity: f32) -> f32 {
+        let n_perceived =
+            self.being_inputs.len() + self.food_obstruct_inputs.len() + self.speechlet_inputs.len();
+        n_perceived as f32 * per_entity
+    }
+
+    // one of the outputs only there when its option is `enabled`; a missing one reads as 0., i.e. left unused
+    fn optional_output(&self, enabled: bool, index: usize) -> f32 {
+        if enabled {
+            self.output[index]
+        } else {
+            0.
+        }
+    }
+
+    // grows or shrinks by `strength` of the resize output, within bounds, paying for the change either way
+    fn resize(&mut self, strength: f32) {
+        let radius = (self.radius + strength * B_RESIZE_RATE).clamp(B_MIN_RADIUS, B_MAX_RADIUS);
+        self.energy_update -= (radius - self.radius).abs() * B_RESIZE_COST;
+        self.radius = radius;
+    }
+
+    // a resized being's upkeep goes with its area
+    fn upkeep_scale(&self, resizing: bool) -> f32 {
+        if resizing {
+            (self.radius / B_RADIUS).powi(2)
+        } else {
+            1.
+        }
+    }
+
+    // a fresh hearing restarts the buffer; with nothing new heard, the last speechlet is heard again, fading
+    // linearly to nothing over `retention` (SPEECHLET_RETENTION) steps
+    fn replay_last_heard(&mut self, retention: usize) {
+        if let Some(row) = self.speechlet_inputs.last() {
+            self.last_heard = Some((row.clone(), 0));
+            return;
+        }
+
+        let Some((row, steps)) = self.last_heard.take() else {
+            return;
+        };
+        if steps < retention {
+            let fade = 1. - (steps + 1) as f32 / (retention + 1) as f32;
+            self.speechlet_inputs
+                .push(row.iter().map(|x| x * fade).collect());
+            self.speechlet_weights.push(1.);
+            self.last_heard = Some((row, steps + 1));
+        }
+    }
+
+    // this step's nearest sightings overwrite their slots at full strength, unseen ones fade
+    fn update_vision_memory(&mut self, decay: f32) {
+        let nearest_food = self
+            .food_obstruct_inputs
+            .iter()
+            .filter(|row| row[0] == 1.)
+            .min_by(|a, b| a[1].total_cmp(&b[1]));
+        match nearest_food {
+            Some(row) => self.vision_memory[0..3].copy_from_slice(&[row[2], row[1], 1.]),
+            None => self.vision_memory[2] *= decay,
+        }
+
+        let nearest_being = self
+            .being_inputs
+            .iter()
+            .min_by(|a, b| a[1].total_cmp(&b[1]));
+        match nearest_being {
+            Some(row) => self.vision_memory[3..6].copy_from_slice(&[row[0], row[1], 1.]),
+            None => self.vision_memory[5] *= decay,
+        }
+    }
+}
+
 pub struct Obstruct {
     pos: Vec2,
     age: f32,
-    id: usize,
+    id: ObstructId,
+}
+
+// what a dead being leaves behind under DeathRemains::Corpse; fed on a bite per step until it's gone
+pub struct Corpse {
+    pos: Vec2,
+    energy: f32,
+    id: CorpseId,
 }
 
 pub struct Food {
@@ -266,7 +1235,7 @@ pub struct Food {
     age: f32,
 
     is_flesh: bool,
-    id: usize,
+    id: FoodId,
 }
 
 #[derive(Debug)]
@@ -275,58 +1244,96 @@ pub struct Speechlet {
     pos: Vec2,
     radius: f32,
     age: f32,
+    emitter_id: BeingId,
 
-    recepient_being_ids: Vec<usize>,
+    recepient_being_ids: HashSet<BeingId>, // a large, long-lived speechlet meets many beings; keep the check O(1)
 }
 
-pub struct World<const D: usize> {
+pub struct World {
     beings_and_models: SlotMap<DefaultKey, (Being, Model)>,
     obstructs: SlotMap<DefaultKey, Obstruct>,
     foods: SlotMap<DefaultKey, Food>,
     speechlets: SlotMap<DefaultKey, Speechlet>,
+    corpses: SlotMap<DefaultKey, Corpse>,
 
-    being_cells: Vec<Vec<DefaultKey>>,
-    obstruct_cells: Vec<Vec<DefaultKey>>,
-    food_cells: Vec<Vec<DefaultKey>>,
-    speechlet_cells: Vec<Vec<DefaultKey>>,
+    // rebuilt from scratch at the top of check_collisions, never maintained incrementally
+    being_cells: CellIndex,
+    obstruct_cells: CellIndex,
+    food_cells: CellIndex,
+    speechlet_cells: CellIndex,
+    corpse_cells: CellIndex,
 
-    being_id: usize,
-    ob_id: usize,
-    food_id: usize,
+    being_id: BeingId,
+    ob_id: ObstructId,
+    food_id: FoodId,
+    corpse_id: CorpseId,
 
     being_deaths: Vec<(DefaultKey, Vec2)>,
+    dying: Vec<(usize, Model, Vec2, f32)>, // this step's dead as (species, model, position, energy), if ReproductionFirst
     obstruct_deaths: Vec<(DefaultKey, Vec2)>,
     food_deaths: Vec<(DefaultKey, Vec2)>,
     speechlet_deaths: Vec<(DefaultKey, Vec2)>,
+    corpse_deaths: Vec<(DefaultKey, Vec2)>,
 
     fov_indices: Vec<(isize, isize)>,
 
     age: usize,
     generation: usize,
-    last_survivors: Vec<Model>,
+    last_survivors: Vec<Vec<Model>>,          // per species
+    starting_population: Vec<(usize, Model)>, // (species, model) as this generation was spawned
+    food_layout: Option<Vec<(Vec2, f32)>>, // if set, food is only ever placed here, and never respawned
+    terrain: Vec<TerrainCell>,             // per partition cell, fixed for the world's lifetime
+    low_population_steps: usize,           // consecutive steps spent under the immigration floor
+    pheromone: Vec<f32>, // per partition cell, laid by beings and fading every step
+
+    stats: GenerationStats,
+    stats_history: Vec<GenerationStats>,
+    stats_write_error: Option<io::Error>, // the latest failure to append to STATS_CSV_PATH, until taken
+    anomaly_log: Vec<Anomaly>,
+    signal_log: Vec<([f32; SPEECHLET_LEN], [f32; 3])>, // this generation's (speechlet, emitter context) pairs
+    schema: SensorySchema,
+    step_trace: Vec<StepHashes>, // one entry per step since creation, if TRACE_STEPS
+    quality_level: u32,          // see TimeBudget
+    parallelism: Parallelism,
+    phase_timings: PhaseTimings, // summed since creation, if PROFILE_PHASES
+    rng: StdRng, // every draw the world makes; seeded by new_seeded, from entropy otherwise
+    seed: Option<u64>, // what rng was seeded from, if it was, for archives to record
+    being_seed_root: u64, // what every being's own rng is seeded from, under PER_BEING_RNG
+    max_food: usize, // shrinks by MAX_FOOD_REDUCTION each reworld, reset at the start of each stage
+    bounds: (Vec2, Vec2), // see arena_bounds
+    stages: &'static [Stage], // see STAGES
 }
 
-impl<const D: usize> World<D> {
+impl World {
     pub fn new() -> Self {
-        World::<D> {
+        World::with_rng(StdRng::from_entropy())
+    }
+
+    fn with_rng(mut rng: StdRng) -> Self {
+        World {
             beings_and_models: SlotMap::new(),
             obstructs: SlotMap::new(),
             foods: SlotMap::new(),
             speechlets: SlotMap::new(),
+            corpses: SlotMap::new(),
 
-            being_cells: (0..(N_CELLS + 1).pow(2)).map(|_| Vec::new()).collect(),
-            obstruct_cells: (0..(N_CELLS + 1).pow(2)).map(|_| Vec::new()).collect(),
-            food_cells: (0..(N_CELLS + 1).pow(2)).map(|_| Vec::new()).collect(),
-            speechlet_cells: (0..(N_CELLS + 1).pow(2)).map(|_| Vec::new()).collect(),
+            being_cells: CellIndex::new((N_CELLS + 1).pow(2)),
+            obstruct_cells: CellIndex::new((N_CELLS + 1).pow(2)),
+            food_cells: CellIndex::new((N_CELLS + 1).pow(2)),
+            speechlet_cells: CellIndex::new((N_CELLS + 1).pow(2)),
+            corpse_cells: CellIndex::new((N_CELLS + 1).pow(2)),
 
-            being_id: 0,
-            ob_id: 0,
-            food_id: 0,
+            being_id: BeingId::default(),
+            ob_id: ObstructId::default(),
+            food_id: FoodId::default(),
+            corpse_id: CorpseId::default(),
 
             being_deaths: vec![],
+            dying: vec![],
             food_deaths: vec![],
             obstruct_deaths: vec![],
             speechlet_deaths: vec![],
+            corpse_deaths: vec![],
 
             fov_indices: (-B_FOV..=B_FOV)
                 .flat_map(|i| (-B_FOV..=B_FOV).map(move |j| (i, j)))
@@ -335,45 +1342,284 @@ impl<const D: usize> World<D> {
 
             age: 0,
             generation: 0,
-            last_survivors: vec![],
+            last_survivors: (0..N_SPECIES).map(|_| vec![]).collect(),
+            starting_population: vec![],
+            food_layout: None,
+            terrain: TERRAIN.build(&mut rng),
+            pheromone: vec![0.; (N_CELLS + 1).pow(2)],
+            low_population_steps: 0,
+
+            stats: GenerationStats::new(0),
+            stats_history: vec![],
+            stats_write_error: None,
+            anomaly_log: vec![],
+            signal_log: vec![],
+            schema: SensorySchema::standard(),
+            step_trace: vec![],
+            quality_level: 0,
+            parallelism: PARALLELISM,
+            phase_timings: PhaseTimings::default(),
+            // drawn only when it's used, so that the switch leaves the world's own draws where they were
+            being_seed_root: if PER_BEING_RNG { rng.gen() } else { 0 },
+            rng,
+            seed: None,
+            max_food: STAGES.first().map_or(MAX_FOOD, |first| first.max_food),
+            bounds: arena_bounds(),
+            stages: STAGES,
         }
     }
 
     // a world populated as intended, this fn mainly to relieve World::new() of some clutter
     pub fn standard_world() -> Self {
-        let mut world = World::new();
-        let mut rng = thread_rng();
-
-        for _ in 0..B_START_COUNT {
-            world.add_being(
-                B_RADIUS,
-                Vec2::new(
-                    rng.gen_range(B_RADIUS..W_FLOAT - B_RADIUS),
-                    rng.gen_range(B_RADIUS..W_FLOAT - B_RADIUS),
-                ),
-                rng.gen_range(-PI..PI),
-                B_START_ENERGY,
-                [0.; GENOME_LEN],
-                Model::standard_model(&DEVICE),
-            );
-        }
+        World::standard_world_with_seeds(vec![], B_START_COUNT).0
+    }
 
-        unsafe {
-            for _ in 0..MAX_FOOD {
-                world.add_food(
-                    Vec2::new(
-                        rng.gen_range(1.0..W_FLOAT - 1.),
-                        rng.gen_range(1.0..W_FLOAT - 1.),
-                    ),
-                    F_VAL,
-                    false,
-                );
-            }
+    // the standard world, made reproducible: two worlds from the same seed step identically. burn's backend
+    // rng, which every network is drawn from, is seeded too, and reseeded from the world's own by reseed_backend
+    pub fn new_seeded(seed: u64) -> Self {
+        let mut world = World::with_seed(seed);
+        world.populate_with_seeds(vec![], B_START_COUNT);
+        world
+    }
+
+    // the world performance and behaviour regressions are tracked on: the standard world, seeded with
+    // BENCHMARK_SEED. see benchmark()
+    pub fn benchmark_world() -> Self {
+        World::new_seeded(BENCHMARK_SEED)
+    }
+
+    // steps without drawing anything, then sums up where the world got to
+    pub fn run_headless(&mut self, steps: usize) -> RunSummary {
+        for _ in 0..steps {
+            self.step(1);
+        }
+
+        RunSummary {
+            steps,
+            generation: self.generation,
+            beings: self.beings_and_models.len(),
+            foods: self.foods.len(),
+            obstructs: self.obstructs.len(),
+            speechlets: self.speechlets.len(),
+            corpses: self.corpses.len(),
+            energy: self.beings_and_models.values().map(|(b, _)| b.energy).sum(),
         }
+    }
+
+    // an empty world with its rng, and burn's, seeded
+    fn with_seed(seed: u64) -> Self {
+        BACKEND::seed(seed);
+        let mut world = World::with_rng(StdRng::seed_from_u64(seed));
+        world.seed = Some(seed);
+        world
+    }
+
+    // the standard world, with `saved` models placed alongside `fresh_count` random ones. if the two fall
+    // short of B_START_COUNT, more fresh ones make up the difference. seeds are otherwise ordinary beings
+    // under selection; their keys are returned so they can be followed.
+    pub fn standard_world_with_seeds(
+        saved: Vec<Model>,
+        fresh_count: usize,
+    ) -> (Self, Vec<DefaultKey>) {
+        let mut world = World::new();
+        let keys = world.populate_with_seeds(saved, fresh_count);
+        (world, keys)
+    }
+
+    fn populate_with_seeds(&mut self, saved: Vec<Model>, fresh_count: usize) -> Vec<DefaultKey> {
+        let fresh_count = fresh_count.max(B_START_COUNT.saturating_sub(saved.len()));
+        let n_saved = saved.len();
+        let population = saved
+            .into_iter()
+            .chain((0..fresh_count).map(|_| Model::standard_model(&DEVICE)))
+            .enumerate()
+            .map(|(n, model)| (n % N_SPECIES, model))
+            .collect();
+
+        let keys = self.populate(population, &[], true);
+        keys[..n_saved].to_vec()
+    }
+
+    // a fresh world for the same generation, with the same starting networks and food cap but its own luck.
+    // nothing is shared with self
+    pub fn fork_for_replicate(&self, seed: u64) -> Self {
+        let mut world = World::with_rng(StdRng::seed_from_u64(seed));
+
+        world.seed = Some(seed);
+        world.generation = self.generation;
+        world.max_food = self.max_food;
+        world.last_survivors = self.last_survivors.clone();
+        world.stats = GenerationStats::new(self.generation);
+        world.populate(self.starting_population.clone(), &[], true);
 
         world
     }
 
+    // writes manifest.txt (format version, config hash, generation, seed), stats.csv (every finished generation)
+    // and population.txt (a line of `species param param ...` per living being) into dir. only the seed the
+    // world's rng started from is recorded, not where it had got to, so a reloaded world restarts from it
+    pub fn archive(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(
+            dir.join("manifest.txt"),
+            Manifest::current(self.generation, self.seed).to_text(),
+        )?;
+
+        let mut stats = format!("{}\n", GenerationStats::csv_header());
+        for generation in &self.stats_history {
+            stats.push_str(&format!("{}\n", generation.csv_row()));
+        }
+        fs::write(dir.join("stats.csv"), stats)?;
+
+        let population: Vec<String> = self
+            .beings_and_models
+            .values()
+            .map(|(b, model)| {
+                let params: Vec<String> = model.params().iter().map(|p| p.to_string()).collect();
+                format!("{} {}", b.species, params.join(" "))
+            })
+            .collect();
+        fs::write(dir.join("population.txt"), population.join("\n"))
+    }
+
+    // a fresh world at the archived generation, seeded with the archived population and stats history. an archived
+    // seed seeds it too, so every load of one archive runs alike. a malformed archive is InvalidData, never a panic,
+    // and so is one written by a differently configured build, unless the caller allows for that
+    pub fn from_archive(dir: &Path, allow_config_mismatch: bool) -> io::Result<Self> {
+        let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidData, what.to_string());
+
+        let manifest = Manifest::parse(&fs::read_to_string(dir.join("manifest.txt"))?)?;
+        if manifest.format_version != ARCHIVE_FORMAT_VERSION {
+            return Err(invalid("unsupported archive format version"));
+        }
+        if manifest.config_hash != config_hash() && !allow_config_mismatch {
+            return Err(invalid(
+                "archive was written by a differently configured build",
+            ));
+        }
+
+        let stats_history = fs::read_to_string(dir.join("stats.csv"))?
+            .lines()
+            .skip(1)
+            .map(|row| GenerationStats::from_csv_row(row).ok_or_else(|| invalid("bad stats row")))
+            .collect::<io::Result<Vec<GenerationStats>>>()?;
+
+        let n_params = Model::standard_model(&DEVICE).num_params();
+        let population = fs::read_to_string(dir.join("population.txt"))?
+            .lines()
+            .map(|line| {
+                let mut fields = line.split(' ');
+                let species: usize = fields
+                    .next()
+                    .and_then(|f| f.parse().ok())
+                    .filter(|species| *species < N_SPECIES)
+                    .ok_or_else(|| invalid("bad species"))?;
+                let params = fields
+                    .map(|f| f.parse::<f32>().map_err(|_| invalid("bad parameter")))
+                    .collect::<io::Result<Vec<f32>>>()?;
+                if params.len() != n_params {
+                    return Err(invalid("parameter count doesn't match the model's shape"));
+                }
+                Ok((species, Model::standard_model(&DEVICE).load_params(&params)))
+            })
+            .collect::<io::Result<Vec<(usize, Model)>>>()?;
+
+        let mut world = manifest.seed.map_or_else(World::new, World::with_seed);
+        world.generation = manifest.generation;
+        world.stats = GenerationStats::new(manifest.generation);
+        world.stats_history = stats_history;
+        world.populate(population, &[], true);
+
+        Ok(world)
+    }
+
+    // scatter a (species, model) population and, if asked, the food at random, returning the beings' keys in order.
+    // a being with a parent position in `near` lands within OFFSPRING_NEAR_PARENT of it instead, if that's set
+    fn populate(
+        &mut self,
+        population: Vec<(usize, Model)>,
+        near: &[Option<Vec2>],
+        lay_food: bool,
+    ) -> Vec<DefaultKey> {
+        self.populate_under(population, near, lay_food, OFFSPRING_NEAR_PARENT)
+    }
+
+    fn populate_under(
+        &mut self,
+        population: Vec<(usize, Model)>,
+        near: &[Option<Vec2>],
+        lay_food: bool,
+        near_parent: Option<f32>,
+    ) -> Vec<DefaultKey> {
+        for (_, model) in &population {
+            assert_eq!(
+                model.input_widths(),
+                self.schema.widths(),
+                "model inputs don't match the world's sensory schema"
+            );
+        }
+        self.starting_population = population.clone();
+
+        let mut positions = B_SPAWN.positions(population.len(), self.bounds, &mut self.rng);
+        if let Some(radius) = near_parent {
+            for (pos, parent) in positions.iter_mut().zip(near) {
+                if let Some(parent) = parent {
+                    *pos = scatter_near(*parent, radius, self.bounds, &mut self.rng);
+                }
+            }
+        }
+
+        let mut keys = Vec::with_capacity(population.len());
+        for ((species, model), pos) in population.into_iter().zip(positions) {
+            let rotation = B_INITIAL_FACING.sample(&mut self.rng);
+            keys.push(self.add_being(
+                B_RADIUS,
+                pos,
+                rotation,
+                B_START_ENERGY,
+                [0.; GENOME_LEN],
+                species,
+                model,
+            ));
+        }
+
+        if !lay_food {
+            return keys;
+        }
+
+        if let Some(layout) = self.food_layout.clone() {
+            for (pos, val) in layout {
+                self.add_food(pos, val, false);
+            }
+        } else {
+            for _ in 0..self.max_food {
+                if let Some(pos) = self.sample_food_spot() {
+                    let val = F_VALUE_DIST.sample(&mut self.rng);
+                    self.add_food(pos, val, false);
+                }
+            }
+        }
+
+        keys
+    }
+
+    // swaps the food for exactly these (position, value) plants and freezes the layout: repop_foods stops
+    // respawning, and every reworld lays the same plants out again
+    pub fn set_food_layout(&mut self, layout: Vec<(Vec2, f32)>) {
+        self.foods.clear();
+        self.food_deaths.clear();
+        for (pos, val) in &layout {
+            self.add_food(*pos, *val, false);
+        }
+
+        self.food_layout = Some(layout);
+    }
+
+    // back to random food placement and respawning
+    pub fn clear_food_layout(&mut self) {
+        self.food_layout = None;
+    }
+
     pub fn add_being(
         &mut self,
         radius: f32,
@@ -381,58 +1627,83 @@ impl<const D: usize> World<D> {
         rotation: f32,
         health: f32,
         genome: [f32; GENOME_LEN],
+        species: usize,
 
         model: Model,
-    ) {
+    ) -> DefaultKey {
         let (i, j) = pos_to_cell(pos);
+        let id = self.being_id.take_next();
 
         let being = Being {
             radius: radius,
             pos: pos,
             rotation: rotation,
             energy: health,
+            health: B_START_HEALTH,
             genome,
+            age: 0,
+            species,
+            grace_remaining: B_SPAWN_GRACE_STEPS,
+
+            collision_layer: 1 << species,
+            collision_mask: if SAME_SPECIES_PHASE {
+                !(1 << species)
+            } else {
+                u32::MAX
+            },
 
             cell: (i, j),
-            id: self.being_id,
+            id: id,
 
             pos_update: Vec2::new(0., 0.),
             energy_update: 0.,
+            damage_taken: 0.,
+            health_update: 0.,
             rotation_update: 0.,
+            rotated: 0.,
 
             being_inputs: vec![],
             food_obstruct_inputs: vec![],
             speechlet_inputs: vec![],
+            being_weights: vec![],
+            food_obstruct_weights: vec![],
+            speechlet_weights: vec![],
+
+            vision_memory: [0.; 6],
+            last_heard: None,
+            visited_cells: VecDeque::from([(i, j)]),
+            history: VecDeque::new(),
+            rng: PER_BEING_RNG.then(|| {
+                StdRng::seed_from_u64(being_seed(self.being_seed_root, self.generation, id))
+            }),
 
             output: [0.; B_OUTPUT_LEN],
         };
 
-        let k = self.beings_and_models.insert((being, model));
-        let ij = two_to_one((i, j));
-        self.being_cells[ij].push(k);
-
-        self.being_id += 1;
+        self.beings_and_models.insert((being, model))
     }
 
     pub fn add_obstruct(&mut self, pos: Vec2) {
-        let (i, j) = pos_to_cell(pos);
-
         let obstruct = Obstruct {
             pos: pos,
             age: O_START_HEALTH,
-            id: self.ob_id,
+            id: self.ob_id.take_next(),
         };
 
-        let k = self.obstructs.insert(obstruct);
+        self.obstructs.insert(obstruct);
+    }
+
+    pub fn add_corpse(&mut self, pos: Vec2) {
+        let corpse = Corpse {
+            pos: pos,
+            energy: C_START_ENERGY,
+            id: self.corpse_id.take_next(),
+        };
 
-        let ij = two_to_one((i, j));
-        self.obstruct_cells[ij].push(k);
-        self.ob_id += 1;
+        self.corpses.insert(corpse);
     }
 
     pub fn add_food(&mut self, pos: Vec2, val: f32, is_flesh: bool) {
-        let (i, j) = pos_to_cell(pos);
-
         let food = Food {
             pos: pos,
             val: val,
@@ -446,83 +1717,216 @@ impl<const D: usize> World<D> {
             },
             is_flesh: is_flesh,
 
-            id: self.food_id,
+            id: self.food_id.take_next(),
         };
 
-        let k = self.foods.insert(food);
-
-        let ij = two_to_one((i, j));
-        self.food_cells[ij].push(k);
-        self.food_id += 1;
+        self.foods.insert(food);
     }
 
-    pub fn add_speechlet(&mut self, speechlet: [f32; SPEECHLET_LEN], pos: Vec2) {
-        let (i, j) = pos_to_cell(pos);
-
+    pub fn add_speechlet(
+        &mut self,
+        speechlet: [f32; SPEECHLET_LEN],
+        pos: Vec2,
+        emitter_id: BeingId,
+    ) {
         let speechlet = Speechlet {
-            speechlet: speechlet,
-            pos: pos,
+            speechlet,
+            pos,
             radius: S_RADIUS,
             age: S_START_AGE,
-
-            recepient_being_ids: vec![],
+            emitter_id,
+            recepient_being_ids: HashSet::new(),
         };
 
-        let k = self.speechlets.insert(speechlet);
-        let ij = two_to_one((i, j));
-        self.speechlet_cells[ij].push(k);
+        self.speechlets.insert(speechlet);
     }
 
+    // one substep's worth of movement; step calls this `substeps` times, so a step moves and turns each being
+    // by its outputs once in total, however finely it's cut
     pub fn move_beings(&mut self, substeps: usize) {
         let s = substeps as f32;
+        let bounds = self.bounds;
 
-        for _ in 0..substeps {
-            self.beings_and_models
-                .iter_mut()
-                .for_each(|(_, (being, _))| {
-                    let being_rotation = dir_from_theta(being.rotation);
-                    let move_vec = being.output[0] * being_rotation;
-                    let newxy = being.pos
-                        + (move_vec
-                            * (1. - LOW_ENERGY_SPEED_DAMP_RATE)
-                            * (being.energy / B_START_ENERGY)
-                            * B_SPEED);
-
-                    if !oob(newxy, being.radius) {
-                        let pos_update = move_vec / s;
-                        let rot_update = (being.output[1] * PI) / s;
-
-                        being.pos_update += pos_update;
-                        being.rotation_update += (being.output[1] * PI) / s;
-
-                        being.energy_update -= (pos_update.length() / B_SPEED) * B_MOVE_TIRE_RATE;
-                        being.energy_update -= (rot_update.abs() / PI) * B_ROT_TIRE_RATE;
-                    } else {
-                        let move_vec = -dir_from_theta(being.rotation) * 1.5; // hacky
-                        being.pos_update += move_vec / s;
-
-                        being.energy_update -= OOB_PENALTY;
-                    }
-                });
-        }
+        self.beings_and_models
+            .iter_mut()
+            .for_each(|(_, (being, _))| {
+                let being_rotation = dir_from_theta(being.rotation);
+
+                // backing up is slower, and can be made dearer, so that facing matters
+                let reversing = being.output[0] < 0.;
+                let (speed_damp, tire_factor) = if reversing {
+                    (1. - REVERSE_SPEED_DAMP, B_REVERSE_TIRE_FACTOR)
+                } else {
+                    (1., 1.)
+                };
+                let ground = self.terrain[two_to_one(being.cell)];
+                let move_vec = being.output[0] * speed_damp * ground.speed * being_rotation;
+                let newxy = being.pos
+                    + (move_vec
+                        * (1. - LOW_ENERGY_SPEED_DAMP_RATE)
+                        * (being.energy / B_START_ENERGY)
+                        * B_SPEED);
+
+                if !oob_within(newxy, being.radius, bounds) {
+                    let pos_update = move_vec / s;
+
+                    // the cap is on the whole step's turning, and only what survives it is applied and paid for
+                    let rotated = (being.rotated + (being.output[1] * PI) / s)
+                        .clamp(-B_MAX_ROT_PER_STEP, B_MAX_ROT_PER_STEP);
+                    let rot_update = rotated - being.rotated;
+
+                    being.pos_update += pos_update;
+                    being.rotation_update += rot_update;
+                    being.rotated = rotated;
+
+                    being.energy_update -= (pos_update.length() / B_SPEED)
+                        * B_MOVE_TIRE_RATE
+                        * tire_factor
+                        * ground.tire;
+                    being.energy_update -= (rot_update.abs() / PI) * B_ROT_TIRE_RATE;
+                } else {
+                    let move_vec = -dir_from_theta(being.rotation) * 1.5; // hacky
+                    being.pos_update += move_vec / s;
+
+                    being.energy_update -= OOB_PENALTY / s;
+                }
+            });
     }
 
     pub fn grow_speechlets(&mut self) {
         self.speechlets.iter_mut().for_each(|(_, s)| {
-            s.radius += S_RADIUS;
+            s.radius += S_GROW_RATE;
         });
     }
 
+    // a single pass buckets every entity into the cell containing its centre
+    pub fn rebuild_cells(&mut self) {
+        self.being_cells.rebuild(
+            self.beings_and_models
+                .iter()
+                .map(|(k, (b, _))| (k, two_to_one(pos_to_cell(b.pos)))),
+        );
+        self.obstruct_cells.rebuild(
+            self.obstructs
+                .iter()
+                .map(|(k, o)| (k, two_to_one(pos_to_cell(o.pos)))),
+        );
+        self.food_cells.rebuild(
+            self.foods
+                .iter()
+                .map(|(k, f)| (k, two_to_one(pos_to_cell(f.pos)))),
+        );
+        self.speechlet_cells.rebuild(
+            self.speechlets
+                .iter()
+                .map(|(k, s)| (k, two_to_one(pos_to_cell(s.pos)))),
+        );
+        self.corpse_cells.rebuild(
+            self.corpses
+                .iter()
+                .map(|(k, c)| (k, two_to_one(pos_to_cell(c.pos)))),
+        );
+    }
+
+    // the living being whose centre is nearest pos, if any is within `dist`. candidates come from the cell index,
+    // searched a cell wider to cover beings that have moved since it was built. beings due to die this step don't
+    // count, though they're still in the slotmap until tire_beings gets to them
+    fn nearest_being(&self, pos: Vec2, dist: f32) -> Option<DefaultKey> {
+        let w = N_CELLS as isize;
+        let span = (dist / CELL_SIZE_FLOAT).ceil() as isize + 1;
+        let (i, j) = pos_to_cell(pos);
+
+        (-span..=span)
+            .flat_map(|di| (-span..=span).map(move |dj| ((i as isize) + di, (j as isize) + dj)))
+            .filter(|(ni, nj)| *ni >= 0 && *ni < w && *nj >= 0 && *nj < w)
+            .flat_map(|(ni, nj)| {
+                self.being_cells
+                    .cell(two_to_one((ni as usize, nj as usize)))
+            })
+            .filter(|k| !self.being_deaths.iter().any(|(dying, _)| dying == *k))
+            .filter_map(|k| {
+                let (b, _) = self.beings_and_models.get(*k)?;
+                Some((*k, b.pos.distance(pos)))
+            })
+            .filter(|(_, d)| *d < dist)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(k, _)| k)
+    }
+
+    // whether an indexed obstruct's centre lies within `dist` of pos
+    fn obstruct_within(&self, pos: Vec2, dist: f32) -> bool {
+        let w = N_CELLS as isize;
+        let span = (dist / CELL_SIZE_FLOAT).ceil() as isize;
+        let (i, j) = pos_to_cell(pos);
+
+        (-span..=span)
+            .flat_map(|di| (-span..=span).map(move |dj| ((i as isize) + di, (j as isize) + dj)))
+            .filter(|(ni, nj)| *ni >= 0 && *ni < w && *nj >= 0 && *nj < w)
+            .flat_map(|(ni, nj)| {
+                self.obstruct_cells
+                    .cell(two_to_one((ni as usize, nj as usize)))
+            })
+            .filter_map(|k| self.obstructs.get(*k))
+            .any(|o| o.pos.distance(pos) < dist)
+    }
+
+    // whether a circle at pos overlaps no obstruct and no being but `except` (a spawner overlaps what it
+    // spawns). candidates come from the cell index, searched one cell wider than the largest possible overlap
+    // to cover beings that have moved since it was built
+    pub fn position_clear(&self, pos: Vec2, radius: f32, except: Option<DefaultKey>) -> bool {
+        let w = N_CELLS as isize;
+        let largest = if RESIZING { B_MAX_RADIUS } else { B_RADIUS };
+        let span = ((radius + largest.max(O_RADIUS)) / CELL_SIZE_FLOAT).ceil() as isize + 1;
+        let (i, j) = pos_to_cell(pos);
+
+        for di in -span..=span {
+            for dj in -span..=span {
+                let (ni, nj) = ((i as isize) + di, (j as isize) + dj);
+                if ni < 0 || ni >= w || nj < 0 || nj >= w {
+                    continue;
+                }
+                let nij = two_to_one((ni as usize, nj as usize));
+
+                let being_in_the_way = self
+                    .being_cells
+                    .cell(nij)
+                    .iter()
+                    .filter(|k| Some(**k) != except)
+                    .filter_map(|k| self.beings_and_models.get(*k))
+                    .any(|(b, _)| b.pos.distance(pos) < b.radius + radius);
+                let obstruct_in_the_way = self
+                    .obstruct_cells
+                    .cell(nij)
+                    .iter()
+                    .filter_map(|k| self.obstructs.get(*k))
+                    .any(|o| o.pos.distance(pos) < O_RADIUS + radius);
+
+                if being_in_the_way || obstruct_in_the_way {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
     pub fn check_collisions(&mut self, substeps: usize) {
         let w = N_CELLS as isize;
         let s = substeps as f32;
+        let bounds = self.bounds;
+
+        // food -> (closest being reaching it, its distance), settled once everyone has been checked. ordered, so
+        // that food is eaten in the same order from run to run
+        let mut food_claims: BTreeMap<DefaultKey, (DefaultKey, f32)> = BTreeMap::new();
+
+        self.rebuild_cells();
 
         for i in 0..N_CELLS {
             for j in 0..N_CELLS {
                 // for each partition
                 let ij = two_to_one((i, j));
 
-                for id1 in &self.being_cells[ij] {
+                for id1 in self.being_cells.cell(ij) {
                     for (di, dj) in &self.fov_indices {
                         let (ni, nj) = ((i as isize) + di, (j as isize) + dj);
 
@@ -531,40 +1935,55 @@ impl<const D: usize> World<D> {
                             let (ni, nj) = (ni as usize, nj as usize);
                             let nij = two_to_one((ni, nj));
 
-                            for id2 in &self.being_cells[nij] {
+                            for id2 in self.being_cells.cell(nij) {
                                 // for another being in the same or one of the 8 neighbouring cells
                                 if !(id1 == id2) {
                                     let (overlap, centre_dist, c1c2, rel_vec) = b_collides_b(
                                         &self.beings_and_models.get(*id1).unwrap().0,
                                         &self.beings_and_models.get(*id2).unwrap().0,
                                     );
+                                    let b2 = &self.beings_and_models.get(*id2).unwrap().0;
+                                    let b2_layer = b2.collision_layer;
+                                    // per-step speed, 1. at full forward output
+                                    let b2_speed = b2.pos_update.length() * s;
+                                    let b2_pos = b2.pos;
                                     let (b1, _) = self.beings_and_models.get_mut(*id1).unwrap();
-                                    b1.being_inputs.push(Vec::from(rel_vec));
+                                    if !in_blind_spot(b1.pos, b1.rotation, b2_pos, REAR_BLIND_ARC) {
+                                        b1.being_inputs.push(Vec::from(rel_vec));
+                                        b1.being_weights.push(foveal_weight(
+                                            b1.pos,
+                                            b1.rotation,
+                                            b2_pos,
+                                            FOVEATION_STRENGTH,
+                                        ));
+                                    }
 
-                                    if overlap > 0. {
+                                    if overlap > 0. && b1.collision_mask & b2_layer != 0 {
                                         let d_p = overlap / centre_dist * c1c2;
                                         let half_dist = d_p / 1.5;
 
                                         let new_pos = b1.pos - half_dist;
-                                        if !oob(new_pos, b1.radius) {
+                                        if !oob_within(new_pos, b1.radius, bounds) {
                                             b1.pos_update -= half_dist;
                                         }
 
                                         let b1_dir = dir_from_theta(b1.rotation);
                                         let axis_alignment = b1_dir.dot(c1c2.normalize());
 
-                                        if axis_alignment > 0. {
-                                            b1.energy_update -=
-                                                B_HEADON_DAMAGE * axis_alignment / s;
-                                        } else {
-                                            b1.energy_update -=
-                                                B_REAR_DAMAGE * axis_alignment.abs() / s;
+                                        let damage = collision_damage(
+                                            axis_alignment,
+                                            b2_speed,
+                                            COMBAT_SPEED_WEIGHT,
+                                        ) / s;
+                                        if b1.grace_remaining == 0 {
+                                            b1.take_damage(damage);
+                                            b1.damage_taken += damage;
                                         }
                                     }
                                 }
                             }
 
-                            for f_id in &self.food_cells[nij] {
+                            for f_id in self.food_cells.cell(nij) {
                                 // for a food similarly
                                 let (b, __) = self.beings_and_models.get_mut(*id1).unwrap();
                                 let f = self.foods.get_mut(*f_id);
@@ -572,47 +1991,134 @@ impl<const D: usize> World<D> {
                                 let f_ref = f.as_ref().unwrap();
 
                                 let (overlap, rel_vec) = b_collides_f(&b, f_ref);
-                                b.food_obstruct_inputs.push(Vec::from(rel_vec));
+                                if !in_blind_spot(b.pos, b.rotation, f_ref.pos, REAR_BLIND_ARC) {
+                                    b.food_obstruct_inputs.push(Vec::from(rel_vec));
+                                    b.food_obstruct_weights.push(foveal_weight(
+                                        b.pos,
+                                        b.rotation,
+                                        f_ref.pos,
+                                        FOVEATION_STRENGTH,
+                                    ));
+                                }
+
+                                let swept = CONTINUOUS_COLLISION
+                                    && swept_circle_hit(
+                                        b.pos,
+                                        b.pos_update,
+                                        f_ref.pos,
+                                        b.radius + F_RADIUS,
+                                    )
+                                    .is_some();
+
+                                if (overlap > 0. || swept) && !f_ref.eaten {
+                                    if FOOD_GOES_TO_NEAREST {
+                                        let dist = b.pos.distance(f_ref.pos);
+                                        claim_food(&mut food_claims, *f_id, *id1, dist);
+                                    } else {
+                                        b.energy_update += f_ref.val;
+                                        self.food_deaths.push((*f_id, f_ref.pos));
+                                        self.stats.eats += 1;
+                                        f.unwrap().eaten = true;
+                                    }
+                                }
+                            }
+
+                            for c_id in self.corpse_cells.cell(nij) {
+                                // for a corpse, which every being overlapping it bites into
+                                let (b, _) = self.beings_and_models.get_mut(*id1).unwrap();
+                                let c = self.corpses.get_mut(*c_id).unwrap();
+
+                                let (overlap, rel_vec) = b_collides_c(b, c);
+                                if !in_blind_spot(b.pos, b.rotation, c.pos, REAR_BLIND_ARC) {
+                                    b.food_obstruct_inputs.push(Vec::from(rel_vec));
+                                    b.food_obstruct_weights.push(foveal_weight(
+                                        b.pos,
+                                        b.rotation,
+                                        c.pos,
+                                        FOVEATION_STRENGTH,
+                                    ));
+                                }
 
-                                if overlap > 0. && !f_ref.eaten {
-                                    b.energy_update += f_ref.val;
-                                    self.food_deaths.push((*f_id, f_ref.pos));
-                                    f.unwrap().eaten = true;
+                                if overlap > 0. {
+                                    let bite = (C_FEED_RATE / s).min(c.energy);
+                                    c.energy -= bite;
+                                    b.energy_update += bite;
                                 }
                             }
 
-                            for ob_id in &self.obstruct_cells[nij] {
+                            for ob_id in self.obstruct_cells.cell(nij) {
                                 // for an obstruct similarly
                                 let (b, _) = self.beings_and_models.get_mut(*id1).unwrap();
                                 let o = self.obstructs.get_mut(*ob_id).unwrap();
 
                                 let (overlap, centre_dist, c1c2, rel_vec) = b_collides_o(b, o);
-                                b.food_obstruct_inputs.push(Vec::from(rel_vec));
+                                if !in_blind_spot(b.pos, b.rotation, o.pos, REAR_BLIND_ARC) {
+                                    b.food_obstruct_inputs.push(Vec::from(rel_vec));
+                                    b.food_obstruct_weights.push(foveal_weight(
+                                        b.pos,
+                                        b.rotation,
+                                        o.pos,
+                                        FOVEATION_STRENGTH,
+                                    ));
+                                }
+                                let solid = b.collision_mask & O_COLLISION_LAYER != 0;
 
-                                if overlap > 0. {
+                                let mining = b.optional_output(MINING, MINE_OUTPUT);
+                                if overlap > 0. && mining > 0. {
+                                    b.energy_update += mine(o, mining, s);
+                                }
+
+                                if overlap > 0. && solid {
+                                    // an obstruct doesn't give way, so the being takes the whole push out of it
                                     let d_p = overlap / centre_dist * c1c2;
-                                    let half_dist = d_p / 1.5;
-                                    b.pos_update -= half_dist;
+                                    b.pos_update -= d_p;
 
                                     let b_dir = dir_from_theta(b.rotation);
                                     let axis_alignment = b_dir.dot(c1c2.normalize());
 
                                     if axis_alignment > 0. {
-                                        b.energy_update -=
-                                            HEADON_B_HITS_O_DAMAGE * axis_alignment / s;
+                                        b.take_damage(HEADON_B_HITS_O_DAMAGE * axis_alignment / s);
+                                    }
+                                } else if CONTINUOUS_COLLISION && solid {
+                                    // the pending move would pass through the obstruct, so it is cut short at first contact
+                                    if let Some(t) = swept_circle_hit(
+                                        b.pos,
+                                        b.pos_update,
+                                        o.pos,
+                                        b.radius + O_RADIUS,
+                                    ) {
+                                        b.pos_update *= t;
                                     }
                                 }
                             }
 
-                            for s_id in &self.speechlet_cells[nij] {
+                            for s_id in self.speechlet_cells.cell(nij) {
                                 let (b, _) = self.beings_and_models.get_mut(*id1).unwrap();
                                 let s = self.speechlets.get_mut(*s_id).unwrap();
 
                                 let overlap = b_collides_s(&b, &s);
 
-                                if overlap > 0. && !s.recepient_being_ids.contains(&b.id) {
+                                let audible = overlap > 0.
+                                    && !s.recepient_being_ids.contains(&b.id)
+                                    // lest the emitter perceive its own echo
+                                    && !(S_EXCLUDE_EMITTER && s.emitter_id == b.id)
+                                    && !(OBSTRUCTS_BLOCK_SPEECH
+                                        && line_blocked(
+                                            &self.obstructs,
+                                            &self.obstruct_cells,
+                                            s.pos,
+                                            b.pos,
+                                        ));
+
+                                if audible {
+                                    s.recepient_being_ids.insert(b.id);
                                     b.speechlet_inputs.push(Vec::from(s.speechlet));
-                                    s.recepient_being_ids.push(b.id);
+                                    b.speechlet_weights.push(foveal_weight(
+                                        b.pos,
+                                        b.rotation,
+                                        s.pos,
+                                        FOVEATION_STRENGTH,
+                                    ));
                                 }
                             }
                         }
@@ -620,20 +2126,29 @@ impl<const D: usize> World<D> {
                 }
             }
         }
+
+        for (f_id, (b_id, _)) in food_claims {
+            let f = self.foods.get_mut(f_id).unwrap();
+            let (b, _) = self.beings_and_models.get_mut(b_id).unwrap();
+
+            b.energy_update += f.val;
+            self.food_deaths.push((f_id, f.pos));
+            self.stats.eats += 1;
+            f.eaten = true;
+        }
     }
 
-    // reflect changes in rotation, translation, collision resolution, fatigue, aging, death
+    // reflect changes in rotation, translation, collision resolution
     pub fn update_cells(&mut self) {
-        for (k, (b, _)) in &mut self.beings_and_models {
+        let bounds = self.bounds;
+        for (_, (b, _)) in &mut self.beings_and_models {
             let new_pos = b.pos + b.pos_update;
             // println!("{}", b.pos_update.length());
-            b.energy += b.energy_update;
             b.rotation += b.rotation_update;
 
-            b.energy_update = 0.;
             b.rotation_update = 0.;
 
-            if !oob(new_pos, b.radius) {
+            if !oob_within(new_pos, b.radius, bounds) {
                 b.pos = new_pos;
                 b.pos_update = Vec2::ZERO;
 
@@ -642,39 +2157,79 @@ impl<const D: usize> World<D> {
 
                 if !same_partition_index((oi, oj), (i, j)) {
                     b.cell = (i, j);
-
-                    let oij = two_to_one((oi, oj));
-                    let ij = two_to_one((i, j));
-
-                    self.being_cells[oij].retain(|x| *x != k);
-                    self.being_cells[ij].push(k);
+                    b.enter_cell((i, j), EXPLORATION_BONUS);
                 }
             }
         }
     }
 
-    // beings tire and/or die
+    // beings tire, settle the step's energy account, and/or die
     pub fn tire_beings(&mut self) {
-        for (k, (b, _)) in &mut self.beings_and_models {
-            b.energy -= B_TIRE_RATE;
+        self.being_deaths.extend(scan_values(
+            self.parallelism,
+            &mut self.beings_and_models,
+            |(b, _)| {
+                b.energy_update -= B_AGING_MODEL.tire_rate(b.age) * b.upkeep_scale(RESIZING);
+                b.age += 1;
+                b.grace_remaining = b.grace_remaining.saturating_sub(1);
+
+                b.energy = (b.energy + b.energy_update).min(B_MAX_ENERGY);
+                b.energy_update = 0.;
+                b.health += b.health_update;
+                b.health_update = 0.;
+
+                (b.energy <= 0. || b.health <= 0.).then_some(b.pos)
+            },
+        ));
+
+        self.keep_dying(REPRODUCTION_ORDER);
+        self.settle_deaths(MAX_FLESH_FOOD);
+    }
 
-            if b.energy <= 0. {
-                self.being_deaths.push((k, b.pos));
+    // under ReproductionFirst, this step's dead are set aside for reworld to breed from before they're removed
+    fn keep_dying(&mut self, order: DeathReproductionOrder) {
+        self.dying.clear();
+        if let DeathReproductionOrder::ReproductionFirst = order {
+            for (k, pos) in &self.being_deaths {
+                let (b, m) = &self.beings_and_models[*k];
+                self.dying.push((b.species, m.clone(), *pos, b.energy));
             }
         }
+    }
 
-        let mut rng = thread_rng();
+    // flesh is scattered serially, after the scan, so food ids don't depend on thread scheduling
+    fn settle_deaths(&mut self, max_flesh: Option<usize>) {
+        let mut flesh = match max_flesh {
+            Some(_) => self.foods.values().filter(|f| f.is_flesh).count(),
+            None => 0,
+        };
         for (k, pos) in &self.being_deaths.clone() {
             self.beings_and_models.remove(*k);
-            self.being_cells[two_to_one(pos_to_cell(*pos))].retain(|x| x != k);
+
+            match DEATH_REMAINS {
+                DeathRemains::Flesh => {}
+                DeathRemains::Corpse => {
+                    self.add_corpse(*pos);
+                    continue;
+                }
+                DeathRemains::Absorbed => {
+                    self.absorb_remains(*pos);
+                    continue;
+                }
+            }
 
             for _ in 0..B_SCATTER_COUNT {
-                let (theta, dist) = (rng.gen_range(-PI..PI), rng.gen_range(0.0..B_SCATTER_RADIUS));
+                let (theta, dist) = (
+                    self.rng.gen_range(-PI..PI),
+                    self.rng.gen_range(0.0..B_SCATTER_RADIUS),
+                );
                 let dvec = Vec2::new(theta.cos() * dist, theta.sin() * dist);
 
                 let food_pos = *pos + dvec;
-                if !oob(food_pos, F_RADIUS) {
+                let room = max_flesh.is_none_or(|max| flesh < max);
+                if !oob_within(food_pos, F_RADIUS, self.bounds) && room {
                     self.add_food(food_pos, B_DEATH_ENERGY / B_SCATTER_RADIUS as f32, true);
+                    flesh += 1;
                 };
             }
         }
@@ -682,73 +2237,124 @@ impl<const D: usize> World<D> {
         self.being_deaths.clear();
     }
 
+    // under DeathRemains::Absorbed, the nearest being in sight of a death at pos takes its share of the remains.
+    // like any other energy it's only settled, and capped, in tire_beings
+    fn absorb_remains(&mut self, pos: Vec2) {
+        if let Some(heir) = self.nearest_being(pos, B_FOV_PX) {
+            self.beings_and_models[heir].0.energy_update +=
+                DEATH_ABSORPTION_FRACTION * B_REMAINS_ENERGY;
+        }
+    }
+
     // walls crack and/or crumble
     pub fn age_obstructs(&mut self) {
-        for (k, o) in &mut self.obstructs {
-            o.age -= O_AGE_RATE;
-
-            if o.age < 0.05 {
-                self.obstruct_deaths.push((k, o.pos));
-            }
-        }
+        self.obstruct_deaths
+            .extend(scan_values(self.parallelism, &mut self.obstructs, |o| {
+                o.age -= O_AGE_RATE;
+                (o.age < 0.05).then_some(o.pos)
+            }));
 
-        for (k, pos) in &self.obstruct_deaths {
+        for (k, _) in &self.obstruct_deaths {
             self.obstructs.remove(*k);
-            self.obstruct_cells[two_to_one(pos_to_cell(*pos))].retain(|x| x != k);
         }
 
         self.obstruct_deaths.clear();
     }
 
+    pub fn decay_pheromone(&mut self) {
+        self.pheromone
+            .iter_mut()
+            .for_each(|p| *p *= PHEROMONE_DECAY);
+    }
+
+    // corpses rot whether or not they're fed on, and vanish once nothing is left
+    pub fn age_corpses(&mut self) {
+        self.corpse_deaths
+            .extend(scan_values(self.parallelism, &mut self.corpses, |c| {
+                c.energy -= C_ROT_RATE;
+                (c.energy <= 0.).then_some(c.pos)
+            }));
+
+        for (k, _) in &self.corpse_deaths {
+            self.corpses.remove(*k);
+        }
+
+        self.corpse_deaths.clear();
+    }
+
     // food grows stale and/or disappears
     pub fn age_foods(&mut self) {
-        for (k, f) in &mut self.foods {
-            f.age -= F_ROT_RATE;
-            if f.age <= 0. {
-                self.food_deaths.push((k, f.pos));
-            }
-        }
+        self.food_deaths
+            .extend(scan_values(self.parallelism, &mut self.foods, |f| {
+                f.age -= F_ROT_RATE;
+                (f.age <= 0.).then_some(f.pos)
+            }));
 
-        for (k, pos) in &self.food_deaths {
+        for (k, _) in &self.food_deaths {
             self.foods.remove(*k);
-
-            self.food_cells[two_to_one(pos_to_cell(*pos))].retain(|x| x != k);
         }
 
         self.food_deaths.clear();
     }
 
     pub fn soften_speechlets(&mut self) {
-        for (k, s) in &mut self.speechlets {
-            s.age -= S_SOFTEN_RATE;
-
-            if s.age <= 0. {
-                self.speechlet_deaths.push((k, s.pos));
-            }
-        }
+        self.speechlet_deaths
+            .extend(scan_values(self.parallelism, &mut self.speechlets, |s| {
+                s.age -= S_SOFTEN_RATE;
+                (s.age <= 0.).then_some(s.pos)
+            }));
 
-        for (k, pos) in &self.speechlet_deaths {
+        for (k, _) in &self.speechlet_deaths {
             self.speechlets.remove(*k);
-            self.speechlet_cells[two_to_one(pos_to_cell(*pos))].retain(|x| x != k);
         }
 
         self.speechlet_deaths.clear();
     }
 
-    pub fn repop_foods(&mut self) {
-        let mut rng = thread_rng();
-        unsafe {
-            for _ in 0..N_FOOD_SPAWN_PER_STEP {
-                if self
+    // draws up to 1 + FOOD_SPAWN_RETRIES spots in the arena and returns the first far enough from all existing
+    // food and not turned down for the beings in its cell, each of which does so with FOOD_SPAWN_AVOIDANCE.
+    // foods placed this step aren't in food_cells yet, so this scans them all
+    fn sample_food_spot(&mut self) -> Option<Vec2> {
+        self.sample_food_spot_under(FOOD_SPAWN_AVOIDANCE, MIN_FOOD_SEPARATION)
+    }
+
+    fn sample_food_spot_under(&mut self, avoidance: f32, separation: f32) -> Option<Vec2> {
+        for _ in 0..=FOOD_SPAWN_RETRIES {
+            let pos = in_bounds(self.bounds, 1., &mut self.rng);
+            let separated = separation <= 0.
+                || self
                     .foods
-                    .iter()
-                    .filter(|(_, f)| !f.is_flesh)
-                    .collect::<Vec<(DefaultKey, &Food)>>()
-                    .len()
-                    < MAX_FOOD
-                {
-                    let ij = Vec2::new(rng.gen_range(1.0..W_FLOAT), rng.gen_range(1.0..W_FLOAT));
-                    self.add_food(ij, F_VAL, false);
+                    .values()
+                    .all(|f| f.pos.distance(pos) >= separation);
+            let crowd = self.being_cells.cell(two_to_one(pos_to_cell(pos))).len();
+            let avoided =
+                avoidance > 0. && self.rng.gen::<f32>() >= (1. - avoidance).powi(crowd as i32);
+
+            if separated && !avoided {
+                return Some(pos);
+            }
+        }
+        None
+    }
+
+    pub fn repop_foods(&mut self) {
+        self.repop_foods_under(FOOD_CAP_MODE, F_VALUE_DIST);
+    }
+
+    fn repop_foods_under(&mut self, cap_mode: FoodCapMode, value_dist: FoodValueDist) {
+        if self.food_layout.is_some() {
+            return;
+        }
+
+        for _ in 0..N_FOOD_SPAWN_PER_STEP {
+            let counted = match cap_mode {
+                FoodCapMode::Plants => self.foods.values().filter(|f| !f.is_flesh).count(),
+                FoodCapMode::All => self.foods.len(),
+            };
+            if counted < self.max_food {
+                if let Some(ij) = self.sample_food_spot() {
+                    let val = value_dist.sample(&mut self.rng);
+                    self.add_food(ij, val, false);
                 }
             }
         }
@@ -756,60 +2362,102 @@ impl<const D: usize> World<D> {
 
     // has side-effects; probably not worth the effort to separate updates and effects
     pub fn perform_being_outputs(&mut self) {
-        let mut obstruct_queue: Vec<Vec2> = Vec::new();
-        let mut speechlet_queue: Vec<(Vec2, [f32; SPEECHLET_LEN])> = Vec::new();
+        let mut obstruct_queue: Vec<(Vec2, DefaultKey)> = Vec::new();
+        let mut speechlet_queue: Vec<(Vec2, [f32; SPEECHLET_LEN], BeingId, [f32; 3])> = Vec::new();
+        let mut pheromone_queue: Vec<((usize, usize), f32)> = Vec::new();
+        let widths = self.schema.widths();
+        let crowding = self.crowding();
+        let row_cap = TIME_BUDGET.map(|budget| (budget.full_rows >> self.quality_level).max(1));
 
         self.beings_and_models
             .iter_mut()
-            .for_each(|(_, (b, model))| {
-                b.being_inputs.push(vec![-1.; 3 + GENOME_LEN]);
-                b.food_obstruct_inputs.push(vec![-1.; 5]);
-                b.speechlet_inputs.push(vec![-1.; SPEECHLET_LEN]);
-
-                let being_tensor = tensorize_2dvec(
-                    &b.being_inputs,
-                    [b.being_inputs.len(), GENOME_LEN + 3],
-                    &DEVICE,
-                )
-                .no_grad();
-                let fo_tensor = tensorize_2dvec(
-                    &b.food_obstruct_inputs,
-                    [b.food_obstruct_inputs.len(), 5],
-                    &DEVICE,
-                )
-                .no_grad();
-                let speechlet_tensor = tensorize_2dvec(
-                    &b.speechlet_inputs,
-                    [b.speechlet_inputs.len(), SPEECHLET_LEN],
-                    &DEVICE,
-                )
-                .no_grad();
-
-                let mut self_vec = is_border_in_sight(b.pos, b.rotation).to_vec();
-                self_vec.extend([b.energy / B_START_ENERGY]);
-
-                let self_tensor = Tensor::<BACKEND, 1>::from_floats(self_vec.as_slice(), &DEVICE)
-                    .reshape([1, 5])
-                    .no_grad();
+            .for_each(|(k, (b, model))| {
+                if let Some(cap) = row_cap {
+                    b.being_inputs.truncate(cap);
+                    b.food_obstruct_inputs.truncate(cap);
+                    b.speechlet_inputs.truncate(cap);
+                    b.being_weights.truncate(cap);
+                    b.food_obstruct_weights.truncate(cap);
+                    b.speechlet_weights.truncate(cap);
+                }
 
+                // a switched-off sense perceives nothing, so it can't leak in through the flags or vision memory either
+                if !SENSES.being {
+                    b.being_inputs.clear();
+                    b.being_weights.clear();
+                }
+                if !SENSES.food_obstruct {
+                    b.food_obstruct_inputs.clear();
+                    b.food_obstruct_weights.clear();
+                }
+                if !SENSES.speechlet {
+                    b.speechlet_inputs.clear();
+                    b.speechlet_weights.clear();
+                }
+                if SPEECHLET_RETENTION > 0 && SENSES.speechlet {
+                    b.replay_last_heard(SPEECHLET_RETENTION);
+                }
+
+                b.energy_update -= b.perception_cost(B_PERCEPTION_COST);
+
+                // what the being's situation was, should it speak
+                let context = [
+                    b.energy / B_START_ENERGY,
+                    b.food_obstruct_inputs
+                        .iter()
+                        .filter(|row| row[0] == 1.)
+                        .count() as f32,
+                    b.being_inputs.len() as f32,
+                ];
+
+                let empty_flags = [
+                    b.being_inputs.is_empty(),
+                    b.food_obstruct_inputs.is_empty(),
+                    b.speechlet_inputs.is_empty(),
+                ]
+                .map(|empty| if empty { 1. } else { 0. });
+
+                if let Some(decay) = VISION_MEMORY_DECAY {
+                    b.update_vision_memory(decay);
+                }
+
+                // only once the counts, flags and memory above have read the rows unscaled
+                if FOVEATION_STRENGTH > 0. {
+                    foveate(&mut b.being_inputs, &b.being_weights);
+                    foveate(&mut b.food_obstruct_inputs, &b.food_obstruct_weights);
+                    foveate(&mut b.speechlet_inputs, &b.speechlet_weights);
+                }
+
+                let self_vec = own_row(
+                    b,
+                    empty_flags,
+                    &self.terrain,
+                    &self.pheromone,
+                    crowding,
+                    self.bounds,
+                );
+                b.damage_taken = 0.;
+
+                let output = forward_rows(
+                    model,
+                    &mut b.being_inputs,
+                    &mut b.food_obstruct_inputs,
+                    &mut b.speechlet_inputs,
+                    &self_vec,
+                    widths,
+                );
                 b.being_inputs.clear();
                 b.food_obstruct_inputs.clear();
                 b.speechlet_inputs.clear();
-                let model_output = model
-                    .forward(being_tensor, fo_tensor, speechlet_tensor, self_tensor)
-                    .into_data()
-                    .value;
-
-                let mut output = [0.; B_OUTPUT_LEN];
-                (0..B_OUTPUT_LEN).into_iter().for_each(|i| {
-                    output[i] = model_output[i];
-                });
+                b.being_weights.clear();
+                b.food_obstruct_weights.clear();
+                b.speechlet_weights.clear();
 
                 b.output = output;
 
                 if b.output[2] > 0. {
                     b.energy_update -= SPAWN_O_RATIO * B_START_ENERGY;
-                    obstruct_queue.push(b.pos + dir_from_theta(b.rotation) * 2.);
+                    obstruct_queue.push((b.pos + dir_from_theta(b.rotation) * 2., k));
                 }
 
                 if b.output[3] > 0. {
@@ -818,208 +2466,1008 @@ impl<const D: usize> World<D> {
                         speechlet[i] = b.output[i + 3];
                     });
                     b.energy_update -= SPAWN_S_RATIO * B_START_ENERGY;
-                    speechlet_queue.push((b.pos, speechlet));
+                    speechlet_queue.push((b.pos, speechlet, b.id, context));
+                }
+
+                let deposit = b.optional_output(PHEROMONES, PHEROMONE_OUTPUT);
+                if deposit > 0. {
+                    pheromone_queue.push((b.cell, deposit.min(1.) * PHEROMONE_DEPOSIT));
+                }
+
+                let growth = b.optional_output(RESIZING, RESIZE_OUTPUT);
+                if growth != 0. {
+                    b.resize(growth);
                 }
             });
 
-        for pos in obstruct_queue {
-            self.add_obstruct(pos);
+        self.place_obstructs(obstruct_queue);
+        for (cell, amount) in pheromone_queue {
+            self.pheromone[two_to_one(cell)] += amount;
         }
-        for (pos, speechlet) in speechlet_queue {
-            self.add_speechlet(speechlet, pos);
+
+        for (pos, speechlet, emitter_id, context) in speechlet_queue {
+            self.signal_log.push((speechlet, context));
+            self.add_speechlet(speechlet, pos, emitter_id);
         }
     }
 
-    pub fn reworld(&mut self) {
-        if self.beings_and_models.len() < REWORLDING_THRESHOLD {
-            unsafe {
-                if MAX_FOOD > MIN_FOOD {
-                    MAX_FOOD -= MAX_FOOD_REDUCTION;
+    // spawns the queued obstructs, each beside the being that paid for it, refunding any that don't fit
+    fn place_obstructs(&mut self, queue: Vec<(Vec2, DefaultKey)>) {
+        self.place_obstructs_under(queue, O_PLACEMENT);
+    }
+
+    fn place_obstructs_under(
+        &mut self,
+        queue: Vec<(Vec2, DefaultKey)>,
+        placement: ObstructPlacement,
+    ) {
+        // obstructs placed this step aren't indexed yet, so they're checked against separately
+        let mut placed: Vec<Vec2> = vec![];
+        for (pos, k) in queue {
+            let pos = placement.snap(pos);
+            // a grid node takes one obstruct, whether or not spawns otherwise need clearance
+            let node_free = match placement {
+                ObstructPlacement::Free => true,
+                ObstructPlacement::Grid { .. } => {
+                    !self.obstruct_within(pos, O_RADIUS)
+                        && placed.iter().all(|p| p.distance(pos) >= O_RADIUS)
                 }
+            };
+            // an obstruct that would stick out of the arena is turned down like any other blocked spawn
+            let clear = node_free
+                && !oob_within(pos, O_RADIUS, self.bounds)
+                && (!O_SPAWN_NEEDS_CLEARANCE
+                    || (self.position_clear(pos, O_RADIUS, Some(k))
+                        && placed.iter().all(|p| p.distance(pos) >= 2. * O_RADIUS)));
+
+            if clear {
+                self.add_obstruct(pos);
+                placed.push(pos);
+            } else if let Some((b, _)) = self.beings_and_models.get_mut(k) {
+                b.energy_update += SPAWN_O_RATIO * B_START_ENERGY;
             }
-            println!("generation: {}, world age: {}", self.generation, self.age);
+        }
+    }
 
-            let mut surviving_models: Vec<Model> = self
-                .beings_and_models
-                .iter_mut()
-                .map(|(_, (_, m))| m.clone())
-                .collect();
+    // a mask of 0 makes a ghost that perceives everything and bumps into nothing
+    pub fn set_collision_mask(&mut self, k: DefaultKey, mask: u32) {
+        if let Some((b, _)) = self.beings_and_models.get_mut(k) {
+            b.collision_mask = mask;
+        }
+    }
 
-            let mut new_models: Vec<Model> = vec![];
+    // the last computed output of every being, keyed as in beings_and_models
+    pub fn being_outputs(&self) -> HashMap<DefaultKey, [f32; B_OUTPUT_LEN]> {
+        self.beings_and_models
+            .iter()
+            .map(|(k, (b, _))| (k, b.output))
+            .collect()
+    }
 
-            let mut rng = thread_rng();
-            if surviving_models.len() == 0 {
-                println!("extinction");
-                new_models = self.last_survivors.clone();
-            } else {
-                while new_models.len() + surviving_models.len() < B_START_COUNT {
-                    let m1 = surviving_models.choose(&mut thread_rng()).unwrap();
-                    let m2 = surviving_models.choose(&mut thread_rng()).unwrap();
-
-                    let new_model = m1
-                        .clone()
-                        .crossover(m2.clone(), 0.05, &DEVICE)
-                        .mutate(0.01, &DEVICE);
-                    new_models.push(new_model);
-                }
-                self.last_survivors = surviving_models.clone();
-            }
+    // beings per cell, as of the last collision check
+    pub fn cell_occupancy_stats(&self) -> OccupancyStats {
+        let counts: Vec<usize> = (0..N_CELLS)
+            .flat_map(|i| (0..N_CELLS).map(move |j| two_to_one((i, j))))
+            .map(|ij| self.being_cells.cell(ij).len())
+            .collect();
 
-            self.beings_and_models.clear();
-            self.foods.clear();
-            self.obstructs.clear();
-            self.speechlets.clear();
+        OccupancyStats::from_counts(&counts)
+    }
 
-            self.being_deaths.clear();
-            self.food_deaths.clear();
-            self.obstruct_deaths.clear();
-            self.speechlet_deaths.clear();
+    pub fn species_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; N_SPECIES];
+        self.beings_and_models
+            .iter()
+            .for_each(|(_, (b, _))| counts[b.species] += 1);
 
-            self.being_cells = (0..(N_CELLS + 1).pow(2)).map(|_| Vec::new()).collect();
-            self.obstruct_cells = (0..(N_CELLS + 1).pow(2)).map(|_| Vec::new()).collect();
-            self.food_cells = (0..(N_CELLS + 1).pow(2)).map(|_| Vec::new()).collect();
-            self.speechlet_cells = (0..(N_CELLS + 1).pow(2)).map(|_| Vec::new()).collect();
-
-            unsafe {
-                for _ in 0..MAX_FOOD {
-                    self.add_food(
-                        Vec2::new(
-                            rng.gen_range(1.0..W_FLOAT - 1.),
-                            rng.gen_range(1.0..W_FLOAT - 1.),
-                        ),
-                        F_VAL,
-                        false,
-                    );
-                }
-            }
+        counts
+    }
 
-            self.being_id = 0;
-            self.ob_id = 0;
-            self.food_id = 0;
+    // count this step's actions towards the generation's stats
+    pub fn tally_actions(&mut self) {
+        self.stats.steps += 1;
+        self.stats.being_steps += self.beings_and_models.len();
 
-            self.age = 0;
-            self.generation += 1;
+        for (_, (b, _)) in &self.beings_and_models {
+            if b.output[0] > 0. {
+                self.stats.moves_forward += 1;
+            } else if b.output[0] < 0. {
+                self.stats.moves_backward += 1;
+            }
+            self.stats.rotation_magnitude += (b.output[1] * PI).abs();
 
-            surviving_models.extend(new_models);
-            for m in surviving_models {
-                self.add_being(
-                    B_RADIUS,
-                    Vec2::new(
-                        rng.gen_range(B_RADIUS..W_FLOAT - B_RADIUS),
-                        rng.gen_range(B_RADIUS..W_FLOAT - B_RADIUS),
-                    ),
-                    rng.gen_range(-PI..PI),
-                    B_START_ENERGY,
-                    [0.; GENOME_LEN],
-                    m,
-                );
+            if b.output[2] > 0. {
+                self.stats.obstruct_spawns += 1;
+            }
+            if b.output[3] > 0. {
+                self.stats.speechlet_emissions += 1;
             }
         }
     }
 
-    pub fn step(&mut self, substeps: usize) {
-        for _ in 0..substeps {
-            self.move_beings(substeps);
-            self.check_collisions(substeps);
-            self.update_cells();
+    // pearson correlation of every speechlet channel (rows) with the emitter's energy, the number of foods
+    // and the number of beings it perceived (columns), over every emission this generation. channels that
+    // track the speaker's situation score near ±1, noise near 0. constant series count as uncorrelated.
+    pub fn signal_correlation(&self) -> [[f32; 3]; SPEECHLET_LEN] {
+        let mut correlation = [[0.; 3]; SPEECHLET_LEN];
+
+        for channel in 0..SPEECHLET_LEN {
+            let signal: Vec<f32> = self.signal_log.iter().map(|(s, _)| s[channel]).collect();
+            for variable in 0..3 {
+                let context: Vec<f32> = self.signal_log.iter().map(|(_, c)| c[variable]).collect();
+                correlation[channel][variable] = pearson(&signal, &context);
+            }
         }
-        self.perform_being_outputs();
-        self.grow_speechlets();
-        self.tire_beings();
-        self.age_foods();
-        self.age_obstructs();
-        self.soften_speechlets();
-        self.repop_foods();
 
-        self.reworld();
+        correlation
+    }
 
+    pub fn stats_history(&self) -> &Vec<GenerationStats> {
+        &self.stats_history
+    }
+
+    pub fn phase_timings(&self) -> PhaseTimings {
+        self.phase_timings
+    }
+
+    pub fn set_parallelism(&mut self, mode: Parallelism) {
+        self.parallelism = mode;
+    }
+
+    pub fn set_max_food(&mut self, max_food: usize) {
+        self.max_food = max_food;
+    }
+
+    // the settings every being's forward runs under, then the architecture of the beings' models, which all share
+    // one shape; read off a living being, or off a fresh standard model if none are left
+    pub fn model_architecture_summary(&self) -> String {
+        let architecture = match self.beings_and_models.values().next() {
+            Some((_, model)) => model.architecture(),
+            None => Model::standard_model(&DEVICE).architecture(),
+        };
+        format!(
+            "pooling: {:?}, senses: {:?}, empty inputs: {:?}, wall sense: {:?}\n{}",
+            POOLING, SENSES, EMPTY_INPUT_POLICY, WALL_SENSE, architecture
+        )
+    }
+
+    // burn's rng is global to the process, so before each batch of networks is drawn, crossed or mutated it's
+    // reseeded from the world's. a seeded world's networks then don't depend on what else has drawn from it
+    fn reseed_backend(&mut self) {
+        BACKEND::seed(self.rng.gen());
+    }
+
+    pub fn diversity(&self) -> f32 {
+        model_diversity(self.beings_and_models.values().map(|(_, m)| m))
+    }
+
+    // a one-off heavy mutation of every living being, on top of the steady per-generation rate
+    pub fn apply_catastrophe(&mut self, strength: f32) {
+        self.anomaly_log.push(Anomaly::Catastrophe {
+            generation: self.generation,
+            diversity: self.diversity(),
+        });
+        self.reseed_backend();
+        for (_, m) in self.beings_and_models.values_mut() {
+            *m = m.clone().mutate(strength, &DEVICE);
+        }
+    }
+
+    // the stats file going unwritable doesn't stop the world, so it's up to the caller to check for it
+    pub fn take_stats_write_error(&mut self) -> Option<io::Error> {
+        self.stats_write_error.take()
+    }
+
+    // every anomaly flagged so far, oldest first
+    pub fn anomaly_log(&self) -> &Vec<Anomaly> {
+        &self.anomaly_log
+    }
+
+    pub fn step_trace(&self) -> &Vec<StepHashes> {
+        &self.step_trace
+    }
+
+    // two runs that should be identical can be compared step by step with trace::first_divergence
+    fn state_hashes(&self) -> StepHashes {
+        [
+            hash_floats(
+                self.beings_and_models
+                    .values()
+                    .flat_map(|(b, _)| [b.pos.x, b.pos.y, b.rotation, b.energy]),
+            ),
+            hash_floats(
+                self.foods
+                    .values()
+                    .flat_map(|f| [f.pos.x, f.pos.y, f.val, f.age]),
+            ),
+            hash_floats(
+                self.obstructs
+                    .values()
+                    .flat_map(|o| [o.pos.x, o.pos.y, o.age]),
+            ),
+            hash_floats(
+                self.speechlets
+                    .values()
+                    .flat_map(|s| [s.pos.x, s.pos.y, s.radius, s.age]),
+            ),
+            hash_floats(
+                self.corpses
+                    .values()
+                    .flat_map(|c| [c.pos.x, c.pos.y, c.energy]),
+            ),
+        ]
+    }
+
+    // whether the generation ends now, for having too few survivors or, given a cap, for having run long enough
+    fn reworld_due(&self, max_generation_age: Option<usize>) -> bool {
+        let timed_out = max_generation_age.is_some_and(|max_age| self.age >= max_age);
+        let stage = stage_for(self.stages, self.generation);
+        let threshold = stage.map_or(REWORLDING_THRESHOLD, |stage| stage.reworld_threshold);
+        timed_out || self.beings_and_models.len() < threshold
+    }
+
+    pub fn reworld(&mut self) {
+        self.reworld_under(REWORLD_CLEARS_FOOD);
+    }
+
+    fn reworld_under(&mut self, clears_food: bool) {
+        if self.reworld_due(MAX_GENERATION_AGE) {
+            let stage = stage_for(self.stages, self.generation);
+            if self.max_food > MIN_FOOD {
+                self.max_food -= MAX_FOOD_REDUCTION;
+            }
+            println!(
+                "generation: {}, world age: {}, species counts: {:?}",
+                self.generation,
+                self.age,
+                self.species_counts()
+            );
+
+            let stats =
+                std::mem::replace(&mut self.stats, GenerationStats::new(self.generation + 1));
+            if let Some(path) = STATS_CSV_PATH {
+                if let Err(e) = stats.append_csv(&PathBuf::from(path)) {
+                    self.stats_write_error = Some(e);
+                }
+            }
+            let anomalies =
+                Anomaly::from_stats(&self.stats_history, &stats, ANOMALY_DIE_OFF_FRACTION);
+            self.anomaly_log.extend(anomalies);
+            self.stats_history.push(stats);
+            self.signal_log.clear();
+
+            self.reseed_backend();
+            let species_target = B_START_COUNT / N_SPECIES;
+            let mutation_rate =
+                OFFSPRING_MUTATION_RATE.unwrap_or(stage.map_or(0.01, |stage| stage.mutation_rate));
+            let mut next_population: Vec<(usize, Model)> = vec![];
+            // lines up with next_population: the first parent's position for bred offspring, None for the rest
+            let mut parent_positions: Vec<Option<Vec2>> = vec![];
+
+            // each species is selected and bred in isolation; parents are only ever crossed within a species
+            for species in 0..N_SPECIES {
+                let dying = self
+                    .dying
+                    .iter()
+                    .filter(|(s, _, _, _)| *s == species)
+                    .map(|(_, m, pos, energy)| (m.clone(), *pos, *energy));
+                let survivors: Vec<(Model, Vec2, f32)> = self
+                    .beings_and_models
+                    .values()
+                    .filter(|(b, _)| b.species == species)
+                    .map(|(b, m)| (m.clone(), b.pos, b.energy))
+                    .chain(dying)
+                    .collect();
+                let survivor_positions: Vec<Vec2> = survivors.iter().map(|s| s.1).collect();
+                let fitness: Vec<f32> = survivors.iter().map(|s| s.2).collect();
+                let mut surviving_models: Vec<Model> = survivors.into_iter().map(|s| s.0).collect();
+
+                let mut new_models: Vec<Model> = vec![];
+                let mut new_positions: Vec<Option<Vec2>> = vec![];
+
+                if surviving_models.is_empty() {
+                    // an extinct species is revived from its last survivors, or from scratch if it never had any
+                    self.anomaly_log.push(Anomaly::Extinction {
+                        generation: self.generation,
+                        species,
+                    });
+                    new_models = self.last_survivors[species].clone();
+                    if new_models.is_empty() {
+                        new_models = (0..species_target)
+                            .map(|_| Model::standard_model(&DEVICE))
+                            .collect();
+                    }
+                    new_positions = vec![None; new_models.len()];
+                } else {
+                    while new_models.len() + surviving_models.len() < species_target {
+                        let (new_model, first_parent) = breed(
+                            &mut self.rng,
+                            &surviving_models,
+                            CONTINUOUS_SELECTION.then_some(fitness.as_slice()),
+                            mutation_rate,
+                        );
+                        new_models.push(new_model);
+                        new_positions.push(Some(survivor_positions[first_parent]));
+                    }
+                    self.last_survivors[species] = surviving_models.clone();
+                }
+
+                parent_positions.extend(surviving_models.iter().map(|_| None));
+                parent_positions.extend(new_positions);
+
+                surviving_models.extend(new_models);
+                next_population.extend(surviving_models.into_iter().map(|m| (species, m)));
+            }
+
+            self.beings_and_models.clear();
+            self.obstructs.clear();
+            self.speechlets.clear();
+            self.corpses.clear();
+
+            self.being_deaths.clear();
+            self.dying.clear();
+            self.obstruct_deaths.clear();
+            self.speechlet_deaths.clear();
+            self.corpse_deaths.clear();
+
+            // kept food also keeps its ids, so the counter carries on from where it was
+            if clears_food {
+                self.foods.clear();
+                self.food_deaths.clear();
+                self.food_id = FoodId::default();
+            }
+
+            self.rebuild_cells();
+            self.pheromone.fill(0.);
+
+            self.being_id = BeingId::default();
+            self.ob_id = ObstructId::default();
+            self.corpse_id = CorpseId::default();
+
+            self.age = 0;
+            self.low_population_steps = 0;
+            self.generation += 1;
+            if let Some(next) = stage_for(self.stages, self.generation)
+                .filter(|next| next.generations.start == self.generation)
+            {
+                self.max_food = next.max_food;
+            }
+
+            // survivors are cloned out of the old world with whatever recurrent state they'd built up in it
+            for (_, m) in next_population.iter_mut() {
+                m.reset_state();
+            }
+
+            self.populate(next_population, &parent_positions, clears_food);
+
+            // struck on the founders as soon as they're placed, and recorded as the generation's starting
+            // population, so that a replicate of it starts from the same, already mutated, beings
+            let due = CATASTROPHE_INTERVAL.is_some_and(|n| self.generation.is_multiple_of(n))
+                || CATASTROPHE_DIVERSITY_FLOOR.is_some_and(|floor| self.diversity() < floor);
+            if due {
+                self.apply_catastrophe(CATASTROPHE_STRENGTH);
+                self.starting_population = self
+                    .beings_and_models
+                    .values()
+                    .map(|(b, m)| (b.species, m.clone()))
+                    .collect();
+            }
+        }
+    }
+
+    // a population that lingers under the floor, without thinning out enough to reworld, is topped up with
+    // fresh random networks so that a few survivors don't dominate indefinitely
+    // `rate` beings arrive once the population has spent IMMIGRATION_PATIENCE steps under the floor. with a
+    // rate of 0 nothing is counted or drawn, so a seeded world's streams are as if immigration didn't exist
+    pub fn immigrate(&mut self, rate: usize) {
+        if rate == 0 {
+            return;
+        }
+        if self.beings_and_models.len() as f32 >= IMMIGRATION_FLOOR * B_START_COUNT as f32 {
+            self.low_population_steps = 0;
+            return;
+        }
+
+        self.low_population_steps += 1;
+        if self.low_population_steps < IMMIGRATION_PATIENCE {
+            return;
+        }
+        self.low_population_steps = 0;
+
+        self.reseed_backend();
+        // the species take turns from a random one on, so that none is always first in line
+        let first_species = self.rng.gen_range(0..N_SPECIES);
+        for n in 0..rate {
+            let (pos, rotation) = (
+                in_bounds(self.bounds, B_RADIUS, &mut self.rng),
+                B_INITIAL_FACING.sample(&mut self.rng),
+            );
+            self.add_being(
+                B_RADIUS,
+                pos,
+                rotation,
+                B_START_ENERGY,
+                [0.; GENOME_LEN],
+                (first_species + n) % N_SPECIES,
+                Model::standard_model(&DEVICE),
+            );
+        }
+    }
+
+    pub fn record_trajectories(&mut self) {
+        self.record_trajectories_under(TRAJECTORY_LEN, TRAJECTORY_EVERY);
+    }
+
+    fn record_trajectories_under(&mut self, len: usize, every: usize) {
+        for (b, _) in self.beings_and_models.values_mut() {
+            if b.age % every == 0 {
+                b.history.push_back(b.pos);
+                if b.history.len() > len {
+                    b.history.pop_front();
+                }
+            }
+        }
+    }
+
+    // the current head count against the one a generation starts with
+    pub fn crowding(&self) -> f32 {
+        self.beings_and_models.len() as f32 / B_START_COUNT as f32
+    }
+
+    // every entity's kind, position, size, heading and energy right now, as compact json. cheap enough to
+    // call every frame
+    pub fn to_scene_json(&self) -> String {
+        // energy and age are filled in for the kinds that have them, and left null for the rest
+        let entity = |kind, id: Option<u64>, pos: Vec2, radius, rotation| SceneEntity {
+            kind,
+            id,
+            x: pos.x,
+            y: pos.y,
+            radius,
+            rotation,
+            energy: None,
+            age: None,
+        };
+
+        let mut entities = Vec::with_capacity(
+            self.beings_and_models.len()
+                + self.foods.len()
+                + self.obstructs.len()
+                + self.speechlets.len()
+                + self.corpses.len(),
+        );
+        entities.extend(self.beings_and_models.values().map(|(b, _)| SceneEntity {
+            energy: Some(b.energy),
+            ..entity("being", Some(b.id.into()), b.pos, b.radius, b.rotation)
+        }));
+        entities.extend(self.foods.values().map(|f| SceneEntity {
+            energy: Some(f.val),
+            ..entity("food", Some(f.id.into()), f.pos, F_RADIUS, 0.)
+        }));
+        entities.extend(self.obstructs.values().map(|o| SceneEntity {
+            age: Some(o.age),
+            ..entity("obstruct", Some(o.id.into()), o.pos, O_RADIUS, 0.)
+        }));
+        entities.extend(self.speechlets.values().map(|s| SceneEntity {
+            age: Some(s.age),
+            ..entity("speechlet", None, s.pos, s.radius, 0.)
+        }));
+        entities.extend(self.corpses.values().map(|c| SceneEntity {
+            energy: Some(c.energy),
+            ..entity("corpse", Some(c.id.into()), c.pos, C_RADIUS, 0.)
+        }));
+
+        Scene {
+            age: self.age,
+            generation: self.generation,
+            entities,
+        }
+        .to_json()
+    }
+
+    // the being's own rng, if PER_BEING_RNG gave it one
+    pub fn being_rng(&mut self, key: DefaultKey) -> Option<&mut StdRng> {
+        self.beings_and_models
+            .get_mut(key)
+            .and_then(|(b, _)| b.rng.as_mut())
+    }
+
+    pub fn trajectory(&self, key: DefaultKey) -> Option<&VecDeque<Vec2>> {
+        self.beings_and_models.get(key).map(|(b, _)| &b.history)
+    }
+
+    // runs a being's model on hand-made sensory rows, its own row taken from its current state. the model is
+    // cloned first, so recurrent state is left as it was and the world doesn't advance
+    pub fn debug_forward(
+        &self,
+        key: DefaultKey,
+        mut being_inputs: Vec<Vec<f32>>,
+        mut fo_inputs: Vec<Vec<f32>>,
+        mut speechlet_inputs: Vec<Vec<f32>>,
+    ) -> Option<[f32; B_OUTPUT_LEN]> {
+        let (b, model) = self.beings_and_models.get(key)?;
+        let crowding = self.crowding();
+        let empty_flags = [&being_inputs, &fo_inputs, &speechlet_inputs].map(|rows| {
+            if rows.is_empty() {
+                1.
+            } else {
+                0.
+            }
+        });
+        let self_vec = own_row(
+            b,
+            empty_flags,
+            &self.terrain,
+            &self.pheromone,
+            crowding,
+            self.bounds,
+        );
+
+        Some(forward_rows(
+            &mut model.clone(),
+            &mut being_inputs,
+            &mut fo_inputs,
+            &mut speechlet_inputs,
+            &self_vec,
+            self.schema.widths(),
+        ))
+    }
+
+    // a golden Scenario of a being's debug_forward outputs; its own row is read off its current state, so a
+    // replay should run against a being in the same state, such as the same key of a reloaded archive
+    pub fn record_scenario(
+        &self,
+        key: DefaultKey,
+        observations: Vec<Observation>,
+    ) -> Option<Scenario> {
+        self.beings_and_models.get(key)?;
+        Some(Scenario::record(observations, |(being, fo, speechlet)| {
+            self.debug_forward(key, being, fo, speechlet)
+                .unwrap()
+                .to_vec()
+        }))
+    }
+
+    pub fn check_scenario(
+        &self,
+        key: DefaultKey,
+        scenario: &Scenario,
+        tolerance: f32,
+    ) -> Option<Result<(), ScenarioMismatch>> {
+        self.beings_and_models.get(key)?;
+        Some(scenario.check(
+            |(being, fo, speechlet)| {
+                self.debug_forward(key, being, fo, speechlet)
+                    .unwrap()
+                    .to_vec()
+            },
+            tolerance,
+        ))
+    }
+
+    fn adapt_quality(&mut self, budget: TimeBudget, spent: Duration) {
+        if spent > budget.step {
+            self.quality_level = (self.quality_level + 1).min(budget.max_level);
+        } else if spent < budget.step / 2 {
+            self.quality_level = self.quality_level.saturating_sub(1);
+        }
+    }
+
+    // a being's energy is settled exactly once per step, in tire_beings. until then every source only adds to
+    // energy_update, in this order: movement, rotation and oob penalties (move_beings); collision damage, eating,
+    // feeding on corpses and mining (check_collisions); exploration (update_cells); perception, spawning and
+    // resizing costs, less refunds for obstructs that found no room (perform_being_outputs); fatigue (tire_beings).
+    // the sum is applied and clamped in one go, so the net change doesn't depend on phase order. the remains a
+    // death leaves under DeathRemains::Absorbed reach the heir's energy_update after its settlement, and are
+    // settled with its next step
+    pub fn step(&mut self, substeps: usize) {
+        if TRACE_STEPS {
+            self.step_trace.push(self.state_hashes());
+        }
+
+        let started = Instant::now();
+        // adds the time since the last lap to `phase`. a no-op unless profiling
+        let mut lap = started;
+        let mut clock = |phase: &mut Duration| {
+            if PROFILE_PHASES {
+                let now = Instant::now();
+                *phase += now - lap;
+                lap = now;
+            }
+        };
+
+        for _ in 0..substeps {
+            self.move_beings(substeps);
+            clock(&mut self.phase_timings.moving);
+            self.check_collisions(substeps);
+            self.update_cells();
+            clock(&mut self.phase_timings.collision);
+        }
+        // the rotation cap spans every substep, so what's been turned is only forgotten once they're all done
+        self.beings_and_models
+            .values_mut()
+            .for_each(|(b, _)| b.rotated = 0.);
+        self.perform_being_outputs();
+        clock(&mut self.phase_timings.forward);
+        if let Some(budget) = TIME_BUDGET {
+            self.adapt_quality(budget, started.elapsed());
+        }
+        self.tally_actions();
+        self.grow_speechlets();
+        self.tire_beings();
+        self.age_foods();
+        self.age_corpses();
+        self.age_obstructs();
+        self.soften_speechlets();
+        if PHEROMONES {
+            self.decay_pheromone();
+        }
+        if TRAJECTORY_LEN > 0 {
+            self.record_trajectories();
+        }
+        clock(&mut self.phase_timings.aging);
+        self.repop_foods();
+        self.immigrate(IMMIGRATION_RATE);
+
+        self.reworld();
+        clock(&mut self.phase_timings.reworld);
+
+        if PROFILE_PHASES {
+            self.phase_timings.steps += 1;
+        }
         self.age += 1;
     }
 }
 
-struct MainState<const D: usize> {
+// a procedurally drawn stand-in for one of the resource pngs, `size` pixels square so the same draw params
+// apply. `inside` is asked about each pixel in coordinates running from -1. to 1. across the image
+fn shape_image(ctx: &Context, size: u32, rgb: [u8; 3], inside: impl Fn(f32, f32) -> bool) -> Image {
+    let mut pixels = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let (u, v) = (
+                (x as f32 + 0.5) / size as f32 * 2. - 1.,
+                (y as f32 + 0.5) / size as f32 * 2. - 1.,
+            );
+            let alpha = if inside(u, v) { 255 } else { 0 };
+            pixels.extend([rgb[0], rgb[1], rgb[2], alpha]);
+        }
+    }
+
+    Image::from_pixels(ctx, &pixels, ImageFormat::Rgba8UnormSrgb, size, size)
+}
+
+struct MainState {
     being_instances: InstanceArray,
     obstruct_instances: InstanceArray,
     food_instances: InstanceArray,
     speechlet_instances: InstanceArray,
-    world: World<D>,
+    corpse_instances: InstanceArray,
+    world: World,
+
+    sim_steps_per_draw: usize,
+    energy_bars: bool,
+    console: Option<String>, // the line being typed, while the console is open
+    console_reply: String,   // what the last command said back
 }
 
-impl<const D: usize> MainState<D> {
-    fn new(ctx: &mut Context, w: World<D>) -> GameResult<MainState<D>> {
-        let being = Image::from_path(ctx, "/red_circle.png")?;
-        let obstruct = Image::from_path(ctx, "/white_circle.png")?;
+impl MainState {
+    fn new(ctx: &mut Context, w: World) -> GameResult<MainState> {
+        // the red png would filter a genome colour down to its red channel
+        let being = if COLOR_BY_GENOME {
+            shape_image(ctx, 400, [255, 255, 255], |u, v| u * u + v * v <= 1.)
+        } else {
+            Image::from_path(ctx, "/red_circle.png")?
+        };
         let food = Image::from_path(ctx, "/green_circle.png")?;
-        let speechlet = Image::from_path(ctx, "/blue_circle.png")?;
+        // likewise the blue speechlet, which is only drawn white to be tinted
+        let speechlet_rgb = if COLOR_SPEECHLETS {
+            [255, 255, 255]
+        } else {
+            [0, 0, 255]
+        };
+        let (obstruct, speechlet) = if DISTINCT_SHAPES {
+            (
+                shape_image(ctx, 800, [255, 255, 255], |_, _| true),
+                shape_image(ctx, 512, speechlet_rgb, |u, v| {
+                    (0.8..=1.).contains(&(u * u + v * v).sqrt())
+                }),
+            )
+        } else if COLOR_SPEECHLETS {
+            (
+                Image::from_path(ctx, "/white_circle.png")?,
+                shape_image(ctx, 512, speechlet_rgb, |u, v| u * u + v * v <= 1.),
+            )
+        } else {
+            (
+                Image::from_path(ctx, "/white_circle.png")?,
+                Image::from_path(ctx, "/blue_circle.png")?,
+            )
+        };
 
         let being_instances = InstanceArray::new(ctx, being);
         let obstruct_instances = InstanceArray::new(ctx, obstruct);
         let food_instances = InstanceArray::new(ctx, food);
         let speechlet_instances = InstanceArray::new(ctx, speechlet);
+        let corpse_instances = InstanceArray::new(
+            ctx,
+            shape_image(ctx, 400, [128, 0, 0], |u, v| u * u + v * v <= 1.),
+        );
 
         Ok(MainState {
             being_instances: being_instances,
             obstruct_instances: obstruct_instances,
             food_instances: food_instances,
             speechlet_instances: speechlet_instances,
+            corpse_instances: corpse_instances,
             world: w,
+
+            sim_steps_per_draw: SIM_STEPS_PER_DRAW,
+            energy_bars: DRAW_ENERGY_BARS,
+            console: None,
+            console_reply: String::new(),
         })
     }
+
+    fn run_command(&mut self, command: Command) -> String {
+        match command {
+            Command::MaxFood(max_food) => self.world.set_max_food(max_food),
+            Command::StepsPerDraw(steps) => self.sim_steps_per_draw = steps,
+            Command::Parallelism(mode) => self.world.set_parallelism(mode),
+            Command::EnergyBars(on) => self.energy_bars = on,
+        }
+        format!("ok: {:?}", command)
+    }
 }
 
-impl<const D: usize> event::EventHandler<ggez::GameError> for MainState<D> {
-    fn update(&mut self, ctx: &mut Context) -> Result<(), ggez::GameError> {
-        self.world.step(1);
+impl event::EventHandler<ggez::GameError> for MainState {
+    fn update(&mut self, _ctx: &mut Context) -> Result<(), ggez::GameError> {
+        for _ in 0..self.sim_steps_per_draw {
+            self.world.step(1);
+        }
+        match self.world.take_stats_write_error() {
+            Some(e) => Err(ggez::GameError::CustomError(format!(
+                "could not write stats: {}",
+                e
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        input: KeyInput,
+        repeated: bool,
+    ) -> Result<(), ggez::GameError> {
+        if let Some(line) = &mut self.console {
+            match input.keycode {
+                Some(KeyCode::Return) => {
+                    let line = self.console.take().unwrap();
+                    self.console_reply = match console::parse(&line) {
+                        Ok(command) => self.run_command(command),
+                        Err(message) => message,
+                    };
+                }
+                Some(KeyCode::Escape) => self.console = None,
+                Some(KeyCode::Back) => {
+                    line.pop();
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match input.keycode {
+            Some(KeyCode::Grave) if !repeated => self.console = Some(String::new()),
+            Some(KeyCode::Up) => {
+                self.sim_steps_per_draw = self
+                    .sim_steps_per_draw
+                    .saturating_mul(2)
+                    .min(MAX_STEPS_PER_DRAW)
+            }
+            Some(KeyCode::Down) => self.sim_steps_per_draw = (self.sim_steps_per_draw / 2).max(1),
+            Some(KeyCode::E) if !repeated => self.energy_bars = !self.energy_bars,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn text_input_event(
+        &mut self,
+        _ctx: &mut Context,
+        character: char,
+    ) -> Result<(), ggez::GameError> {
+        // the ` that opened the console arrives here too
+        if let Some(line) = &mut self.console {
+            if !character.is_control() && character != '`' {
+                line.push(character);
+            }
+        }
         Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> Result<(), ggez::GameError> {
         let mut canvas = Canvas::from_frame(ctx, Color::BLACK);
         if self.world.generation % VIS_FREQUENCY == 0 {
-            self.speechlet_instances
-                .set(self.world.speechlets.iter().map(|(_, s)| {
-                    let xy = s.pos;
-                    DrawParam::new()
-                        .scale(Vec2::new(1., 1.) / 512. * s.radius)
-                        .dest(xy)
-                        .offset(Vec2::new(256., 256.))
-                        .color(Color::new(1., 1., 1., s.age / S_START_AGE))
-                }));
-
-            self.food_instances
-                .set(self.world.foods.iter().map(|(_, f)| {
-                    let xy = f.pos - Vec2::new(F_RADIUS, F_RADIUS);
-                    DrawParam::new()
-                        .dest(xy.clone())
-                        .scale(Vec2::new(1., 1.) / 2048. * 2. * F_RADIUS)
-                        .color(Color::new(1., 1., 1., f.val / F_VAL))
-                }));
-
-            self.obstruct_instances
-                .set(self.world.obstructs.iter().map(|(_, o)| {
-                    let xy = o.pos;
-                    DrawParam::new()
-                        .dest(xy.clone())
-                        .scale(Vec2::new(1., 1.) / 800. * 2. * O_RADIUS)
-                        .color(Color::new(1., 1., 1., o.age / O_START_HEALTH))
-                }));
-
-            self.being_instances
-                .set(self.world.beings_and_models.iter().map(|(_, (b, _))| {
-                    let xy = b.pos;
-                    DrawParam::new()
-                        .scale(Vec2::new(1., 1.) / 400. * 2. * B_RADIUS)
-                        .dest(xy)
-                        .offset(Vec2::new(200., 200.))
-                        .rotation(b.rotation)
-                        .color(Color::new(1., 1., 1., b.energy / B_START_ENERGY))
-                }));
+            self.speechlet_instances.set(
+                self.world
+                    .speechlets
+                    .iter()
+                    .filter(|(_, s)| s.age / S_START_AGE >= DRAW_ALPHA_THRESHOLD)
+                    .map(|(_, s)| {
+                        let xy = s.pos;
+                        let mut color = if COLOR_SPEECHLETS {
+                            speechlet_color(&s.speechlet)
+                        } else {
+                            Color::WHITE
+                        };
+                        color.a = s.age / S_START_AGE;
+                        DrawParam::new()
+                            .scale(Vec2::new(1., 1.) / 512. * s.radius)
+                            .dest(xy)
+                            .offset(Vec2::new(256., 256.))
+                            .color(color)
+                    }),
+            );
+
+            self.food_instances.set(
+                self.world
+                    .foods
+                    .iter()
+                    .filter(|(_, f)| f.val / F_VALUE_DIST.max() >= DRAW_ALPHA_THRESHOLD)
+                    .map(|(_, f)| {
+                        let xy = f.pos - Vec2::new(F_RADIUS, F_RADIUS);
+                        DrawParam::new()
+                            .dest(xy.clone())
+                            .scale(Vec2::new(1., 1.) / 2048. * 2. * F_RADIUS)
+                            .color(Color::new(1., 1., 1., f.val / F_VALUE_DIST.max()))
+                    }),
+            );
+
+            self.corpse_instances.set(
+                self.world
+                    .corpses
+                    .iter()
+                    .filter(|(_, c)| c.energy / C_START_ENERGY >= DRAW_ALPHA_THRESHOLD)
+                    .map(|(_, c)| {
+                        DrawParam::new()
+                            .scale(Vec2::new(1., 1.) / 400. * 2. * C_RADIUS)
+                            .dest(c.pos)
+                            .offset(Vec2::new(200., 200.))
+                            .color(Color::new(1., 1., 1., c.energy / C_START_ENERGY))
+                    }),
+            );
+
+            self.obstruct_instances.set(
+                self.world
+                    .obstructs
+                    .iter()
+                    .filter(|(_, o)| o.age / O_START_HEALTH >= DRAW_ALPHA_THRESHOLD)
+                    .map(|(_, o)| {
+                        let xy = o.pos;
+                        DrawParam::new()
+                            .dest(xy.clone())
+                            .scale(Vec2::new(1., 1.) / 800. * 2. * O_RADIUS)
+                            .color(Color::new(1., 1., 1., o.age / O_START_HEALTH))
+                    }),
+            );
+
+            self.being_instances.set(
+                self.world
+                    .beings_and_models
+                    .iter()
+                    .map(|(_, (b, _))| {
+                        let alpha = if DRAW_HEALTH_AS_ALPHA {
+                            b.health / B_START_HEALTH
+                        } else {
+                            b.energy / B_START_ENERGY
+                        };
+                        (b, alpha)
+                    })
+                    .filter(|(_, alpha)| *alpha >= DRAW_ALPHA_THRESHOLD)
+                    .map(|(b, alpha)| {
+                        let xy = b.pos;
+                        let tint = 1. - 0.75 * B_AGING_MODEL.tint(b.age);
+                        let color = if COLOR_BY_GENOME {
+                            Color {
+                                a: alpha,
+                                ..b.genome_color()
+                            }
+                        } else {
+                            Color::new(tint, tint, tint, alpha)
+                        };
+                        DrawParam::new()
+                            .scale(Vec2::new(1., 1.) / 400. * 2. * b.radius)
+                            .dest(xy)
+                            .offset(Vec2::new(200., 200.))
+                            .rotation(b.rotation)
+                            .color(color)
+                    }),
+            );
+
+            if DRAW_TRAILS {
+                let mut trails = MeshBuilder::new();
+                let mut empty = true;
+                for (b, _) in self.world.beings_and_models.values() {
+                    let points: Vec<Vec2> = b.history.iter().copied().chain([b.pos]).collect();
+                    for (n, segment) in points.windows(2).enumerate() {
+                        let alpha = 0.5 * (n + 1) as f32 / points.len() as f32;
+                        trails.line(segment, 0.5, Color::new(1., 1., 1., alpha))?;
+                        empty = false;
+                    }
+                }
+                if !empty {
+                    canvas.draw(&Mesh::from_data(ctx, trails.build()), DrawParam::new());
+                }
+            }
 
             let param = DrawParam::new();
             canvas.draw(&self.speechlet_instances, param);
             canvas.draw(&self.food_instances, param);
+            canvas.draw(&self.corpse_instances, param);
             canvas.draw(&self.obstruct_instances, param);
             canvas.draw(&self.being_instances, param);
+
+            // a being's width of dark bar just above it, filled in green up to its share of B_START_ENERGY
+            if self.energy_bars && !self.world.beings_and_models.is_empty() {
+                let mut bars = MeshBuilder::new();
+                for (b, _) in self.world.beings_and_models.values() {
+                    let corner = b.pos - Vec2::new(b.radius, b.radius + 3.);
+                    let fill = (b.energy / B_START_ENERGY).clamp(0., 1.);
+                    bars.rectangle(
+                        DrawMode::fill(),
+                        Rect::new(corner.x, corner.y, 2. * b.radius, 1.),
+                        Color::new(0.2, 0.2, 0.2, 1.),
+                    )?;
+                    bars.rectangle(
+                        DrawMode::fill(),
+                        Rect::new(corner.x, corner.y, 2. * b.radius * fill, 1.),
+                        Color::GREEN,
+                    )?;
+                }
+                canvas.draw(&Mesh::from_data(ctx, bars.build()), DrawParam::new());
+            }
+        }
+        canvas.draw(
+            &Text::new(format!("x{}", self.sim_steps_per_draw)),
+            DrawParam::new().dest(Vec2::new(4., 4.)).color(Color::WHITE),
+        );
+        if SHOW_OCCUPANCY_HUD {
+            let occupancy = self.world.cell_occupancy_stats();
+            canvas.draw(
+                &Text::new(format!(
+                    "cells max {} mean {:.2} var {:.2} empty {:.2}",
+                    occupancy.max, occupancy.mean, occupancy.variance, occupancy.empty_fraction
+                )),
+                DrawParam::new()
+                    .dest(Vec2::new(4., 20.))
+                    .color(Color::WHITE),
+            );
+        }
+        if PROFILE_PHASES {
+            canvas.draw(
+                &Text::new(self.world.phase_timings().summary()),
+                DrawParam::new()
+                    .dest(Vec2::new(4., 36.))
+                    .color(Color::WHITE),
+            );
+        }
+        if let Some(line) = &self.console {
+            canvas.draw(
+                &Text::new(format!("> {}_", line)),
+                DrawParam::new()
+                    .dest(Vec2::new(4., 52.))
+                    .color(Color::WHITE),
+            );
+        }
+        if !self.console_reply.is_empty() {
+            canvas.draw(
+                &Text::new(self.console_reply.as_str()),
+                DrawParam::new()
+                    .dest(Vec2::new(4., 68.))
+                    .color(Color::WHITE),
+            );
         }
         let a = canvas.finish(ctx);
 
@@ -1027,8 +3475,9 @@ impl<const D: usize> event::EventHandler<ggez::GameError> for MainState<D> {
     }
 }
 
-pub fn run() -> GameResult {
-    let world = World::<2>::standard_world();
+pub fn run(seed: Option<u64>) -> GameResult {
+    let world = seed.map_or_else(World::standard_world, World::new_seeded);
+    println!("{}", world.model_architecture_summary());
 
     let resource_dir = if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
         let mut path = PathBuf::from(manifest_dir);
@@ -1060,17 +3509,1583 @@ pub fn run() -> GameResult {
     event::run(ctx, event_loop, state)
 }
 
-pub fn gauge() {
-    let mut w = World::<2>::standard_world();
+pub fn gauge(seed: Option<u64>) {
+    let mut w = seed.map_or_else(World::standard_world, World::new_seeded);
     loop {
         w.step(1);
     }
 }
 
+// where BENCHMARK_STEPS of the benchmark world end up under the settings as committed. any change that moves
+// this has changed behaviour; if that was intended, the new summary goes here
+pub const BENCHMARK_SUMMARY: RunSummary = RunSummary {
+    steps: BENCHMARK_STEPS,
+    generation: 0,
+    beings: 128,
+    foods: 10591,
+    obstructs: 646,
+    speechlets: 3451,
+    corpses: 0,
+    energy: 776.73566,
+};
+
+// times BENCHMARK_STEPS of the benchmark world and says whether it ended up at BENCHMARK_SUMMARY, for
+// comparing a build's speed and behaviour against an earlier one's
+pub fn benchmark() {
+    let started = Instant::now();
+    let mut world = World::benchmark_world();
+    let summary = world.run_headless(BENCHMARK_STEPS);
+    println!("{:?} in {:?}", summary, started.elapsed());
+    if summary != BENCHMARK_SUMMARY {
+        println!("behaviour has drifted, expected {:?}", BENCHMARK_SUMMARY);
+    }
+
+    // a rerun from the same seed should retrace the first step for step; where it doesn't, the trace says
+    // which step and subsystem to look at
+    if TRACE_STEPS {
+        let mut rerun = World::benchmark_world();
+        rerun.run_headless(BENCHMARK_STEPS);
+        if let Some((step, subsystems)) =
+            trace::first_divergence(world.step_trace(), rerun.step_trace())
+        {
+            println!(
+                "not reproducible: the rerun diverged at step {} in {:?}",
+                step, subsystems
+            );
+        }
+    }
+}
+
 pub fn main() {
     assert!(W_SIZE % N_CELLS == 0);
     assert!(B_RADIUS < CELL_SIZE as f32);
+    assert!(!RESIZING || 2. * B_MAX_RADIUS < B_FOV_PX);
+    assert!(
+        ARENA.is_none_or(|arena| arena.x >= 0.
+            && arena.y >= 0.
+            && arena.right() <= W_FLOAT
+            && arena.bottom() <= W_FLOAT),
+        "ARENA must lie within the world"
+    );
+    assert!(
+        stages_are_contiguous(STAGES),
+        "STAGES must cover contiguous generation ranges from 0"
+    );
+
+    // gauge(WORLD_SEED);
+    // benchmark();
+    _ = run(WORLD_SEED);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, MutexGuard};
+
+    // burn's rng is global to the process, so tests that draw networks take turns. otherwise a seeded world's
+    // draws could interleave with another test's
+    pub(crate) fn backend_turn() -> MutexGuard<'static, ()> {
+        static TURN: Mutex<()> = Mutex::new(());
+        TURN.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn centre() -> Vec2 {
+        Vec2::splat(W_FLOAT / 2.)
+    }
+
+    // a being facing right, at its start energy, with an all-zero genome and a fresh standard network
+    fn add_plain_being(world: &mut World, pos: Vec2) -> DefaultKey {
+        let model = Model::standard_model(&DEVICE);
+        world.add_being(
+            B_RADIUS,
+            pos,
+            0.,
+            B_START_ENERGY,
+            [0.; GENOME_LEN],
+            0,
+            model,
+        )
+    }
+
+    // a world holding a single plain being mid-arena, with its outputs fixed
+    fn lone_being(output: [f32; B_OUTPUT_LEN]) -> (World, DefaultKey) {
+        let mut world = World::with_rng(StdRng::seed_from_u64(0));
+        let k = add_plain_being(&mut world, centre());
+        world.beings_and_models[k].0.output = output;
+        world.rebuild_cells();
+        (world, k)
+    }
+
+    // the substep phases of World::step, with none of the rest
+    fn substeps(world: &mut World, substeps: usize) {
+        for _ in 0..substeps {
+            world.move_beings(substeps);
+            world.check_collisions(substeps);
+            world.update_cells();
+        }
+        world
+            .beings_and_models
+            .values_mut()
+            .for_each(|(b, _)| b.rotated = 0.);
+    }
+
+    #[test]
+    fn rotation_and_its_cost_dont_depend_on_substeps() {
+        let _turn = backend_turn();
+        let mut output = [0.; B_OUTPUT_LEN];
+        output[0] = 0.5;
+        output[1] = 0.8;
+
+        let (mut once, k1) = lone_being(output);
+        let (mut often, k4) = lone_being(output);
+        substeps(&mut once, 1);
+        substeps(&mut often, 4);
+
+        let (b1, b4) = (
+            &once.beings_and_models[k1].0,
+            &often.beings_and_models[k4].0,
+        );
+        assert!((b1.rotation - b4.rotation).abs() < 1e-5);
+        assert!((b1.rotation - 0.8 * PI).abs() < 1e-5);
+        assert!((b1.energy_update - b4.energy_update).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rotation_is_capped_over_the_whole_step() {
+        let _turn = backend_turn();
+        let mut output = [0.; B_OUTPUT_LEN];
+        output[1] = 2. * B_MAX_ROT_PER_STEP / PI;
+
+        let (mut world, k) = lone_being(output);
+        substeps(&mut world, 4);
+
+        let b = &world.beings_and_models[k].0;
+        assert!((b.rotation - B_MAX_ROT_PER_STEP).abs() < 1e-5);
+    }
+
+    // two worlds from the same seed, stepped in lockstep, so that any draw one makes from burn's shared rng
+    // lands between the other's. a reworld is forced midway by culling both below the threshold
+    #[test]
+    fn same_seed_worlds_step_identically_across_a_reworld() {
+        let _turn = backend_turn();
+        let mut worlds = [World::new_seeded(7), World::new_seeded(7)];
+        let mut traces = [vec![], vec![]];
+        for n in 0..12 {
+            for (world, trace) in worlds.iter_mut().zip(&mut traces) {
+                if n == 6 {
+                    let kept: Vec<DefaultKey> = world
+                        .beings_and_models
+                        .keys()
+                        .take(REWORLDING_THRESHOLD - 1)
+                        .collect();
+                    world.beings_and_models.retain(|k, _| kept.contains(&k));
+                    world.rebuild_cells();
+                }
+                trace.push(world.state_hashes());
+                world.step(1);
+            }
+        }
+
+        assert!(worlds.iter().all(|world| world.generation == 1));
+        assert_eq!(trace::first_divergence(&traces[0], &traces[1]), None);
+    }
+
+    #[test]
+    fn the_nearest_survivor_absorbs_a_share_of_the_remains() {
+        let _turn = backend_turn();
+        let mut world = World::with_rng(StdRng::seed_from_u64(0));
+        let add = |world: &mut World, dx: f32| add_plain_being(world, centre() + Vec2::new(dx, 0.));
+        let dying = add(&mut world, B_RADIUS);
+        let heir = add(&mut world, 3. * B_RADIUS);
+        let beyond = add(&mut world, 5. * B_RADIUS);
+        world.rebuild_cells();
+
+        // a being that's itself about to die can't inherit
+        world
+            .being_deaths
+            .push((dying, world.beings_and_models[dying].0.pos));
+        world.absorb_remains(centre());
+        let share = DEATH_ABSORPTION_FRACTION * B_REMAINS_ENERGY;
+        assert_eq!(world.beings_and_models[heir].0.energy_update, share);
+        assert_eq!(world.beings_and_models[beyond].0.energy_update, 0.);
+
+        world.being_deaths.clear();
+        world.tire_beings();
+        let expected = (B_START_ENERGY + share - B_AGING_MODEL.tire_rate(0)).min(B_MAX_ENERGY);
+        assert!((world.beings_and_models[heir].0.energy - expected).abs() < 1e-5);
+    }
 
-    // gauge();
-    _ = run();
+    #[test]
+    fn archives_reload_and_malformed_ones_are_refused() {
+        let _turn = backend_turn();
+        let dir = std::env::temp_dir().join(format!("samsarsa-archive-{}", std::process::id()));
+        let world = World::new_seeded(3);
+        world.archive(&dir).unwrap();
+
+        let reloaded = World::from_archive(&dir, false).unwrap();
+        assert_eq!(reloaded.seed, Some(3));
+        assert_eq!(
+            reloaded.beings_and_models.len(),
+            world.beings_and_models.len()
+        );
+
+        let population = fs::read_to_string(dir.join("population.txt")).unwrap();
+        let (truncated, _) = population.rsplit_once(' ').unwrap();
+        fs::write(dir.join("population.txt"), truncated).unwrap();
+        let refused = World::from_archive(&dir, false).err().unwrap();
+        assert_eq!(refused.kind(), io::ErrorKind::InvalidData);
+        fs::write(dir.join("population.txt"), population).unwrap();
+
+        let manifest = fs::read_to_string(dir.join("manifest.txt")).unwrap();
+        let current = format!("config_hash={:016x}", config_hash());
+        let foreign = format!("config_hash={:016x}", config_hash() ^ 1);
+        fs::write(
+            dir.join("manifest.txt"),
+            manifest.replace(&current, &foreign),
+        )
+        .unwrap();
+        let refused = World::from_archive(&dir, false).err().unwrap();
+        assert_eq!(refused.kind(), io::ErrorKind::InvalidData);
+        assert!(World::from_archive(&dir, true).is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // the being the golden scenario was recorded against: a seeded network, alone mid-arena
+    fn golden_being() -> (World, DefaultKey) {
+        let mut world = World::with_seed(11);
+        let k = add_plain_being(&mut world, centre());
+        (world, k)
+    }
+
+    fn golden_observations() -> Vec<Observation> {
+        let [being, fo, speechlet, _] = SensorySchema::standard().widths();
+        let rows = |width: usize, n: usize, value: f32| vec![vec![value; width]; n];
+        vec![
+            (vec![], vec![], vec![]),
+            (rows(being, 1, 0.5), vec![], vec![]),
+            (
+                rows(being, 2, -0.25),
+                rows(fo, 3, 0.75),
+                rows(speechlet, 1, 1.),
+            ),
+        ]
+    }
+
+    fn golden_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/scenarios/lone_being.json")
+    }
+
+    // rewrites the golden scenario. only run it, with --ignored, when a change in model behaviour is intended
+    #[test]
+    #[ignore]
+    fn record_golden_scenario() {
+        let _turn = backend_turn();
+        let (world, k) = golden_being();
+        let scenario = world.record_scenario(k, golden_observations()).unwrap();
+        fs::create_dir_all(golden_path().parent().unwrap()).unwrap();
+        scenario.save(&golden_path()).unwrap();
+    }
+
+    #[test]
+    fn golden_scenario_still_replays() {
+        let _turn = backend_turn();
+        let (world, k) = golden_being();
+        let scenario = Scenario::load(&golden_path()).unwrap();
+        assert_eq!(world.check_scenario(k, &scenario, 1e-5), Some(Ok(())));
+    }
+
+    #[test]
+    fn blind_spot_and_foveal_weight_follow_the_facing() {
+        let pos = Vec2::new(10., 10.);
+        let (ahead, beside, behind) = (pos + Vec2::X, pos + Vec2::Y, pos - Vec2::X);
+        let arc = PI / 2.;
+
+        assert!(in_blind_spot(pos, 0., behind, arc));
+        assert!(!in_blind_spot(pos, 0., beside, arc));
+        assert!(!in_blind_spot(pos, 0., ahead, arc));
+        assert!(!in_blind_spot(pos, PI, behind, arc));
+        assert!(!in_blind_spot(pos, 0., behind, 0.));
+
+        assert!((foveal_weight(pos, 0., ahead, 0.5) - 1.).abs() < 1e-6);
+        assert!((foveal_weight(pos, 0., beside, 0.5) - 0.75).abs() < 1e-6);
+        assert!((foveal_weight(pos, 0., behind, 0.5) - 0.5).abs() < 1e-6);
+        assert!((foveal_weight(pos, PI, behind, 0.5) - 1.).abs() < 1e-6);
+        assert_eq!(foveal_weight(pos, 0., behind, 0.), 1.);
+    }
+
+    #[test]
+    fn a_catastrophe_restores_diversity_to_a_population_of_clones() {
+        let _turn = backend_turn();
+        let mut world = World::with_seed(5);
+        let model = Model::standard_model(&DEVICE);
+        for n in 0..8 {
+            let k = add_plain_being(
+                &mut world,
+                centre() + Vec2::new(n as f32 * 2. * B_RADIUS, 0.),
+            );
+            world.beings_and_models[k].1 = model.clone();
+        }
+
+        let params = |world: &World| -> Vec<Vec<f32>> {
+            world
+                .beings_and_models
+                .values()
+                .map(|(_, m)| m.params())
+                .collect()
+        };
+        let before = weight_diversity(&params(&world));
+        world.apply_catastrophe(0.5);
+        let after = weight_diversity(&params(&world));
+
+        assert!(before < 1e-6);
+        assert!(after > 1e-2, "diversity only rose to {}", after);
+        assert!(matches!(
+            world.anomaly_log().last(),
+            Some(Anomaly::Catastrophe { generation: 0, .. })
+        ));
+    }
+
+    // beings on food and on a corpse gain what they eat and what the corpse loses, less their fatigue, and
+    // nothing else
+    #[test]
+    fn a_step_settles_exactly_its_sources_and_sinks() {
+        let _turn = backend_turn();
+        let mut world = World::with_rng(StdRng::seed_from_u64(0));
+        for n in 0..3 {
+            let pos = centre() + Vec2::new(n as f32 * 6. * B_RADIUS, 0.);
+            add_plain_being(&mut world, pos);
+            match n {
+                0 => world.add_food(pos, 0.3, false),
+                1 => world.add_food(pos, 0.7, true),
+                _ => world.add_corpse(pos),
+            }
+        }
+        world.rebuild_cells();
+
+        let beings = |world: &World| {
+            world
+                .beings_and_models
+                .values()
+                .map(|(b, _)| b.energy)
+                .sum::<f32>()
+        };
+        let corpses = |world: &World| world.corpses.values().map(|c| c.energy).sum::<f32>();
+        let (beings_before, corpses_before) = (beings(&world), corpses(&world));
+
+        world.move_beings(1);
+        world.check_collisions(1);
+        world.update_cells();
+        world.tire_beings();
+
+        let eaten: f32 = world
+            .foods
+            .values()
+            .filter(|f| f.eaten)
+            .map(|f| f.val)
+            .sum();
+        let fed = corpses_before - corpses(&world);
+        let fatigue = 3. * B_AGING_MODEL.tire_rate(0);
+        assert!(eaten > 0. && fed > 0.);
+        assert!((beings(&world) - beings_before - (eaten + fed - fatigue)).abs() < 1e-4);
+    }
+
+    // the benchmark world's own check; slow in a debug build, so run with --ignored, ideally in release
+    #[test]
+    #[ignore]
+    fn benchmark_world_ends_at_the_documented_summary() {
+        let _turn = backend_turn();
+        let summary = World::benchmark_world().run_headless(BENCHMARK_STEPS);
+        assert_eq!(summary, BENCHMARK_SUMMARY);
+    }
+
+    #[test]
+    fn backing_up_is_damped() {
+        let _turn = backend_turn();
+        let moved = |speed: f32| {
+            let mut output = [0.; B_OUTPUT_LEN];
+            output[0] = speed;
+            let (mut world, k) = lone_being(output);
+            let start = world.beings_and_models[k].0.pos;
+            substeps(&mut world, 1);
+            world.beings_and_models[k].0.pos.distance(start)
+        };
+
+        let (forward, backward) = (moved(0.5), moved(-0.5));
+        assert!(forward > 0.);
+        assert!((backward - forward * (1. - REVERSE_SPEED_DAMP)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn old_beings_tire_faster() {
+        let senescence = AgingModel::Senescence { rate: 0.01 };
+        let (young, old) = (senescence.tire_rate(0), senescence.tire_rate(1000));
+
+        assert_eq!(young, B_TIRE_RATE);
+        assert!(old > young);
+        assert_eq!(
+            AgingModel::None.tire_rate(1000),
+            AgingModel::None.tire_rate(0)
+        );
+    }
+
+    #[test]
+    fn an_emitter_does_not_hear_its_own_speechlet() {
+        let _turn = backend_turn();
+        let (mut world, emitter) = lone_being([0.; B_OUTPUT_LEN]);
+        let pos = world.beings_and_models[emitter].0.pos;
+        let listener = add_plain_being(&mut world, pos + Vec2::new(B_RADIUS, 0.));
+        let id = world.beings_and_models[emitter].0.id;
+        world.add_speechlet([1.; SPEECHLET_LEN], pos, id);
+        world.rebuild_cells();
+        world.check_collisions(1);
+
+        assert!(world.beings_and_models[emitter]
+            .0
+            .speechlet_inputs
+            .is_empty());
+        assert_eq!(
+            world.beings_and_models[listener].0.speechlet_inputs.len(),
+            1
+        );
+    }
+
+    // a move far longer than the target is wide, whose endpoints both clear it
+    #[test]
+    fn a_fast_being_cant_tunnel_through_a_small_target() {
+        let (p0, d) = (Vec2::new(10., 10.), Vec2::new(30., 0.));
+        let reach = B_RADIUS + F_RADIUS;
+        let on_path = p0 + Vec2::new(15., 0.);
+        assert!(p0.distance(on_path) > reach && (p0 + d).distance(on_path) > reach);
+
+        let t = swept_circle_hit(p0, d, on_path, reach).unwrap();
+        assert!((t - (15. - reach) / 30.).abs() < 1e-6);
+
+        assert_eq!(
+            swept_circle_hit(p0, d, on_path + Vec2::new(0., 10.), reach),
+            None
+        );
+        assert_eq!(
+            swept_circle_hit(p0, d, p0 + Vec2::new(40., 0.), reach),
+            None
+        );
+        assert_eq!(swept_circle_hit(p0, d, p0, reach), Some(0.));
+    }
+
+    #[test]
+    fn contested_food_goes_to_the_nearer_being() {
+        let mut keys: SlotMap<DefaultKey, ()> = SlotMap::new();
+        let (food, near, far) = (keys.insert(()), keys.insert(()), keys.insert(()));
+
+        for order in [[(near, 1.), (far, 3.)], [(far, 3.), (near, 1.)]] {
+            let mut claims = BTreeMap::new();
+            for (being, dist) in order {
+                claim_food(&mut claims, food, being, dist);
+            }
+            assert_eq!(claims[&food].0, near);
+        }
+    }
+
+    // a being that sees nothing answers the same way every time it's asked
+    #[test]
+    fn a_lone_being_seeing_nothing_is_stable() {
+        let _turn = backend_turn();
+        let (world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        let alone = || world.debug_forward(k, vec![], vec![], vec![]).unwrap();
+
+        let first = alone();
+        assert!(first.iter().all(|x| x.is_finite()));
+        assert_eq!(first, alone());
+    }
+
+    #[test]
+    fn beings_that_mask_out_their_layer_overlap_freely() {
+        let _turn = backend_turn();
+        let pushed = |mask: Option<u32>| {
+            let (mut world, k1) = lone_being([0.; B_OUTPUT_LEN]);
+            let k2 = add_plain_being(&mut world, centre() + Vec2::new(B_RADIUS, 0.));
+            if let Some(mask) = mask {
+                world.set_collision_mask(k1, mask);
+                world.set_collision_mask(k2, mask);
+            }
+            world.rebuild_cells();
+            world.check_collisions(1);
+            [k1, k2].map(|k| world.beings_and_models[k].0.pos_update.length())
+        };
+
+        assert!(pushed(None).iter().all(|&d| d > 0.));
+        assert_eq!(pushed(Some(!(1 << 0))), [0., 0.]);
+    }
+
+    // perform_being_outputs has charged each spawner by the time its obstruct is placed
+    #[test]
+    fn an_obstruct_spawned_onto_an_occupied_spot_is_refused_and_refunded() {
+        let _turn = backend_turn();
+        let spawn = |blocked: bool| {
+            let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+            let ahead = world.beings_and_models[k].0.pos + Vec2::new(2., 0.);
+            if blocked {
+                add_plain_being(&mut world, ahead + Vec2::new(B_RADIUS, 0.));
+                world.rebuild_cells();
+            }
+            // the second spawn lands on the first's obstruct
+            world.place_obstructs(vec![(ahead, k), (ahead, k)]);
+            (
+                world.obstructs.len(),
+                world.beings_and_models[k].0.energy_update,
+            )
+        };
+
+        let refund = SPAWN_O_RATIO * B_START_ENERGY;
+        assert_eq!(spawn(false), (1, refund));
+        assert_eq!(spawn(true), (0, 2. * refund));
+    }
+
+    #[test]
+    fn an_overlapping_newborn_takes_no_damage_during_grace() {
+        let _turn = backend_turn();
+        let collide = |grace: usize| {
+            let (mut world, _) = lone_being([0.; B_OUTPUT_LEN]);
+            let k2 = add_plain_being(&mut world, centre() + Vec2::new(B_RADIUS, 0.));
+            world.beings_and_models[k2].0.grace_remaining = grace;
+            world.rebuild_cells();
+            world.check_collisions(1);
+            let b2 = &world.beings_and_models[k2].0;
+            (b2.energy_update, b2.pos_update.length())
+        };
+
+        let (hurt, pushed) = collide(0);
+        assert!(hurt < 0. && pushed > 0.);
+        assert_eq!(collide(3), (0., pushed));
+    }
+
+    #[test]
+    fn a_healthy_population_still_reworlds_at_the_age_cap() {
+        let _turn = backend_turn();
+        let mut world = World::new_seeded(1);
+        world.age = 50;
+
+        assert!(world.beings_and_models.len() >= REWORLDING_THRESHOLD);
+        assert!(!world.reworld_due(None));
+        assert!(!world.reworld_due(Some(51)));
+        assert!(world.reworld_due(Some(50)));
+    }
+
+    #[test]
+    fn parents_are_chosen_reproducibly_and_apart() {
+        let pairs = |seed: u64, n: usize, allow_self: bool| -> Vec<(usize, usize)> {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..200)
+                .map(|_| choose_parents(&mut rng, n, allow_self))
+                .collect()
+        };
+
+        assert_eq!(pairs(3, 5, false), pairs(3, 5, false));
+        assert_ne!(pairs(3, 5, false), pairs(4, 5, false));
+
+        assert!(pairs(3, 2, false).iter().all(|(i1, i2)| i1 != i2));
+        assert!(pairs(3, 2, true).iter().any(|(i1, i2)| i1 == i2));
+        assert!(pairs(3, 1, false).iter().all(|&pair| pair == (0, 0)));
+    }
+
+    #[test]
+    fn similar_genomes_get_similar_colors() {
+        let channels = |c: Color| [c.r, c.g, c.b];
+        let mut genome = [0.; GENOME_LEN];
+        genome
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, gene)| *gene = (i as f32 * 0.7).sin());
+        let color = channels(genome_color(&genome));
+        assert_eq!(color, channels(genome_color(&genome.clone())));
+
+        // a channel moves by at most half a gene's change over sqrt(GENOME_LEN)
+        let bound = 0.5 * 0.01 / (GENOME_LEN as f32).sqrt() + 1e-6;
+        for i in 0..GENOME_LEN {
+            let mut nudged = genome;
+            nudged[i] += 0.01;
+            let nudged = channels(genome_color(&nudged));
+            assert!(color
+                .iter()
+                .zip(nudged)
+                .all(|(a, b)| (a - b).abs() <= bound));
+        }
+
+        let far = channels(genome_color(&genome.map(|gene| -gene)));
+        assert!(color
+            .iter()
+            .zip(far)
+            .any(|(a, b)| (a - b).abs() > 10. * bound));
+    }
+
+    #[test]
+    fn beings_and_food_stay_within_the_arena() {
+        let _turn = backend_turn();
+        let mut world = World::with_seed(0);
+        world.bounds = bounds_of(Some(Rect::new(40., 60., 100., 80.)));
+        world.max_food = 100;
+        let population = (0..60)
+            .map(|_| (0, Model::standard_model(&DEVICE)))
+            .collect();
+        world.populate(population, &[], true);
+
+        let (min, max) = world.bounds;
+        let inside =
+            |p: Vec2, margin: f32| p.cmpge(min + margin).all() && p.cmple(max - margin).all();
+        for _ in 0..50 {
+            world.step(1);
+            assert!(world
+                .beings_and_models
+                .values()
+                .all(|(b, _)| inside(b.pos, b.radius)));
+            assert!(world.foods.values().all(|f| inside(f.pos, 1.)));
+            assert!(world.obstructs.values().all(|o| inside(o.pos, O_RADIUS)));
+        }
+        assert!(!world.beings_and_models.is_empty());
+        assert!(!world.foods.is_empty());
+
+        // a being backed against the wall can't wall itself in on the far side of it
+        let k = world.beings_and_models.keys().next().unwrap();
+        let obstructs = world.obstructs.len();
+        world.place_obstructs(vec![(min - Vec2::splat(O_RADIUS), k)]);
+        assert_eq!(world.obstructs.len(), obstructs);
+    }
+
+    // a block of cells holding a being each, about a fifth of the world
+    #[test]
+    fn food_spawns_away_from_crowded_cells() {
+        let _turn = backend_turn();
+        let mut world = World::with_rng(StdRng::seed_from_u64(0));
+        let model = Model::standard_model(&DEVICE);
+        let block = 50;
+        for i in 0..block {
+            for j in 0..block {
+                let pos = (Vec2::new(i as f32, j as f32) + 0.5) * CELL_SIZE_FLOAT;
+                let k = add_plain_being(&mut world, pos);
+                world.beings_and_models[k].1 = model.clone();
+            }
+        }
+        world.rebuild_cells();
+
+        let crowded_share = |world: &mut World, avoidance: f32| {
+            let spots: Vec<Vec2> = (0..1000)
+                .filter_map(|_| world.sample_food_spot_under(avoidance, 0.))
+                .collect();
+            let crowded = spots
+                .iter()
+                .filter(|pos| pos.cmplt(Vec2::splat(block as f32 * CELL_SIZE_FLOAT)).all())
+                .count();
+            crowded as f32 / spots.len() as f32
+        };
+
+        let (uniform, avoiding) = (
+            crowded_share(&mut world, 0.),
+            crowded_share(&mut world, 0.9),
+        );
+        assert!(uniform > 0.1, "{}", uniform);
+        assert!(avoiding < 0.05, "{}", avoiding);
+    }
+
+    #[test]
+    fn a_persistently_low_population_receives_immigrants() {
+        let _turn = backend_turn();
+        let (mut world, _) = lone_being([0.; B_OUTPUT_LEN]);
+
+        for _ in 0..2 * IMMIGRATION_PATIENCE {
+            world.immigrate(0);
+        }
+        assert_eq!(world.beings_and_models.len(), 1);
+        assert_eq!(world.low_population_steps, 0);
+
+        for _ in 0..IMMIGRATION_PATIENCE - 1 {
+            world.immigrate(3);
+        }
+        assert_eq!(world.beings_and_models.len(), 1);
+        world.immigrate(3);
+        assert_eq!(world.beings_and_models.len(), 4);
+    }
+
+    #[test]
+    fn scene_reports_age_apart_from_energy() {
+        let mut world = World::with_seed(0);
+        world.add_obstruct(centre());
+        world.add_food(centre() + Vec2::new(50., 0.), 3., false);
+
+        let scene: serde_json::Value = serde_json::from_str(&world.to_scene_json()).unwrap();
+        let entities = scene["entities"].as_array().unwrap();
+        let of_kind = |kind: &str| entities.iter().find(|e| e["kind"] == kind).unwrap();
+
+        assert!(of_kind("obstruct")["energy"].is_null());
+        assert!(of_kind("obstruct")["age"].is_number());
+        assert_eq!(of_kind("food")["energy"], 3.);
+        assert!(of_kind("food")["age"].is_null());
+    }
+
+    #[test]
+    fn mass_die_off_stays_within_the_combined_food_caps() {
+        let _turn = backend_turn();
+        let mut world = World::with_seed(0);
+        world.max_food = 50;
+        let max_flesh = 120;
+
+        for i in 0..20 {
+            let pos = centre() + Vec2::new((i % 5) as f32 * 30., (i / 5) as f32 * 30.);
+            let k = add_plain_being(&mut world, pos);
+            world.being_deaths.push((k, pos));
+        }
+        world.settle_deaths(Some(max_flesh));
+
+        let flesh = world.foods.values().filter(|f| f.is_flesh).count();
+        assert_eq!(flesh, max_flesh);
+        assert!(world.beings_and_models.is_empty());
+
+        for _ in 0..200 {
+            world.repop_foods_under(FoodCapMode::All, F_VALUE_DIST);
+        }
+        assert!(world.foods.len() <= world.max_food + max_flesh);
+    }
+
+    #[test]
+    fn entities_ahead_dominate_the_pooled_perception_under_foveation() {
+        let pos = Vec2::new(10., 10.);
+        let targets = [
+            pos + Vec2::X,
+            pos - Vec2::X,
+            pos - Vec2::X + Vec2::Y,
+            pos - Vec2::X - Vec2::Y,
+        ];
+        // the one entity ahead reads 1., the three behind it -1.
+        let unweighted: Vec<Vec<f32>> = targets
+            .iter()
+            .map(|&t| vec![if t.x > pos.x { 1. } else { -1. }])
+            .collect();
+
+        let pooled = |strength: f32| {
+            let weights: Vec<f32> = targets
+                .iter()
+                .map(|&t| foveal_weight(pos, 0., t, strength))
+                .collect();
+            let mut rows = unweighted.clone();
+            foveate(&mut rows, &weights);
+            let flat: Vec<f32> = rows.concat();
+            let x = Tensor::<BACKEND, 1>::from_floats(flat.as_slice(), &DEVICE)
+                .reshape([rows.len(), 1]);
+            Pooling::Mean.pool(x).into_scalar()
+        };
+
+        assert!(pooled(0.) < 0.);
+        assert!(pooled(0.9) > 0.);
+    }
+
+    #[test]
+    fn a_beings_noise_does_not_depend_on_iteration_order() {
+        let root = StdRng::seed_from_u64(3).gen();
+        let noise = |rng: &mut StdRng| -> Vec<f32> { (0..4).map(|_| rng.gen()).collect() };
+        let rngs = |ids: &[u64]| -> Vec<StdRng> {
+            ids.iter()
+                .map(|&id| StdRng::seed_from_u64(being_seed(root, 2, BeingId(id))))
+                .collect()
+        };
+
+        // the same beings, drawn from one at a time in opposite orders
+        let mut forward = rngs(&[0, 1, 2]);
+        let mut backward = rngs(&[2, 1, 0]);
+        let forward_noise: Vec<Vec<f32>> = forward.iter_mut().map(noise).collect();
+        let mut backward_noise: Vec<Vec<f32>> = backward.iter_mut().map(noise).collect();
+        backward_noise.reverse();
+        assert_eq!(forward_noise, backward_noise);
+
+        assert_ne!(forward_noise[0], forward_noise[1]);
+        assert_ne!(
+            being_seed(root, 2, BeingId(0)),
+            being_seed(root, 3, BeingId(0))
+        );
+    }
+
+    #[test]
+    fn a_being_dying_as_the_generation_ends_parents_only_under_reproduction_first() {
+        let _turn = backend_turn();
+        let parents_under = |order: DeathReproductionOrder| {
+            let mut world = World::with_seed(0);
+            add_plain_being(&mut world, centre());
+            let dying = add_plain_being(&mut world, centre() + Vec2::new(20., 0.));
+            world
+                .being_deaths
+                .push((dying, centre() + Vec2::new(20., 0.)));
+
+            world.keep_dying(order);
+            world.settle_deaths(None);
+            world.reworld();
+            assert_eq!(world.generation, 1);
+            world.last_survivors[0].len()
+        };
+
+        assert_eq!(parents_under(DeathReproductionOrder::DeathFirst), 1);
+        assert_eq!(parents_under(DeathReproductionOrder::ReproductionFirst), 2);
+    }
+
+    #[test]
+    fn breeding_leaves_the_parents_untouched() {
+        let _turn = backend_turn();
+        let mut rng = StdRng::seed_from_u64(0);
+        let parents = vec![
+            Model::standard_model(&DEVICE),
+            Model::standard_model(&DEVICE),
+        ];
+        let fingerprint = |m: &Model| hash_floats(m.params().into_iter());
+        let before: Vec<u64> = parents.iter().map(fingerprint).collect();
+
+        let (mutated, _) = breed(&mut rng, &parents, None, 0.5);
+        let (unmutated, _) = breed(&mut rng, &parents, None, 0.);
+
+        assert_eq!(
+            parents.iter().map(fingerprint).collect::<Vec<u64>>(),
+            before
+        );
+        assert!(!before.contains(&fingerprint(&mutated)));
+        // unmutated, every parameter is one parent's or the other's
+        let (p1, p2) = (parents[0].params(), parents[1].params());
+        assert!(unmutated
+            .params()
+            .iter()
+            .enumerate()
+            .all(|(i, x)| *x == p1[i] || *x == p2[i]));
+    }
+
+    #[test]
+    fn fitter_survivors_are_drawn_as_parents_more_often() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let fitness = [8., 8., 1., 1.];
+        let mut drawn = [0; 4];
+        for _ in 0..4000 {
+            let (i1, i2) = choose_fit_parents(&mut rng, &fitness, false);
+            assert_ne!(i1, i2);
+            drawn[i1] += 1;
+        }
+        assert!(drawn[0] + drawn[1] > 4 * (drawn[2] + drawn[3]));
+
+        // nobody with any fitness left: every survivor is as likely as any other
+        let (i1, i2) = choose_fit_parents(&mut rng, &[0., 0.], false);
+        assert_ne!(i1, i2);
+    }
+
+    #[test]
+    fn perceiving_a_crowd_costs_more_than_being_alone() {
+        let _turn = backend_turn();
+        let (mut alone, k_alone) = lone_being([0.; B_OUTPUT_LEN]);
+        let (mut crowded, k_crowded) = lone_being([0.; B_OUTPUT_LEN]);
+        for n in 0..8 {
+            let offset = Vec2::from_angle(n as f32 * PI / 4.) * 15.;
+            add_plain_being(&mut crowded, centre() + offset);
+            crowded.add_food(centre() - offset * 0.5, F_VAL, false);
+        }
+        crowded.rebuild_cells();
+        substeps(&mut alone, 1);
+        substeps(&mut crowded, 1);
+
+        let cost =
+            |world: &World, k: DefaultKey| world.beings_and_models[k].0.perception_cost(0.01);
+        assert_eq!(cost(&alone, k_alone), 0.);
+        assert!((cost(&crowded, k_crowded) - 16. * 0.01).abs() < 1e-6);
+    }
+
+    #[test]
+    fn the_memory_of_an_eaten_food_persists_and_fades() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        world.add_food(centre() + Vec2::new(3., 0.), F_VAL, false);
+        world.rebuild_cells();
+
+        // as perform_being_outputs and age_foods leave things after each step
+        let observe = |world: &mut World| {
+            substeps(world, 1);
+            let b = &mut world.beings_and_models[k].0;
+            b.update_vision_memory(0.5);
+            b.being_inputs.clear();
+            b.food_obstruct_inputs.clear();
+            world.age_foods();
+            world.beings_and_models[k].0.vision_memory
+        };
+
+        let seen = observe(&mut world);
+        assert!(world.foods.is_empty());
+        assert_eq!(seen[2], 1.);
+        let remembered = observe(&mut world);
+        assert_eq!(remembered[0..2], seen[0..2]);
+        assert_eq!(remembered[2], 0.5);
+        assert_eq!(observe(&mut world)[2], 0.25);
+    }
+
+    #[test]
+    fn a_fixed_food_layout_keeps_its_food_count() {
+        let _turn = backend_turn();
+        let (mut world, _) = lone_being([0.; B_OUTPUT_LEN]);
+        let layout: Vec<(Vec2, f32)> = (0..5)
+            .map(|n| (centre() + Vec2::new(30. + n as f32 * 10., 0.), F_VAL))
+            .collect();
+        world.set_food_layout(layout.clone());
+
+        for _ in 0..20 {
+            world.repop_foods();
+            world.age_foods();
+        }
+        assert_eq!(world.foods.len(), layout.len());
+    }
+
+    #[test]
+    fn food_is_carried_over_a_reworld_that_keeps_it() {
+        let _turn = backend_turn();
+        let food_positions =
+            |world: &World| -> Vec<Vec2> { world.foods.values().map(|f| f.pos).collect() };
+
+        let (mut world, _) = lone_being([0.; B_OUTPUT_LEN]);
+        for n in 0..5 {
+            world.add_food(centre() + Vec2::new(n as f32 * 10., 20.), F_VAL, false);
+        }
+        let before = food_positions(&world);
+        world.reworld_under(false);
+        assert_eq!(world.generation, 1);
+        assert_eq!(food_positions(&world), before);
+
+        let (mut world, _) = lone_being([0.; B_OUTPUT_LEN]);
+        world.add_food(centre() + Vec2::new(0., 20.), F_VAL, false);
+        world.reworld_under(true);
+        assert!(!food_positions(&world).contains(&(centre() + Vec2::new(0., 20.))));
+    }
+
+    #[test]
+    fn a_speechlet_reaches_many_receivers_once_each() {
+        let _turn = backend_turn();
+        let mut world = World::with_seed(0);
+        let keys: Vec<DefaultKey> = (0..20)
+            .map(|n| {
+                let offset = Vec2::from_angle(n as f32 * PI / 10.) * 2.;
+                add_plain_being(&mut world, centre() + offset)
+            })
+            .collect();
+        world.add_speechlet([0.5; SPEECHLET_LEN], centre(), BeingId(999));
+        world.rebuild_cells();
+
+        substeps(&mut world, 1);
+        substeps(&mut world, 1);
+
+        let s = world.speechlets.values().next().unwrap();
+        assert_eq!(s.recepient_being_ids.len(), keys.len());
+        for k in keys {
+            let b = &world.beings_and_models[k].0;
+            assert!(s.recepient_being_ids.contains(&b.id));
+            assert_eq!(b.speechlet_inputs.len(), 1);
+        }
+    }
+
+    #[test]
+    fn a_rammed_being_feels_the_hit() {
+        let _turn = backend_turn();
+        let (mut world, rammer) = lone_being([0.; B_OUTPUT_LEN]);
+        // facing away from the rammer, so it's struck from behind
+        let victim = add_plain_being(&mut world, centre() + Vec2::new(4., 0.));
+        let bystander = add_plain_being(&mut world, centre() + Vec2::new(0., 20.));
+        world.rebuild_cells();
+        substeps(&mut world, 1);
+
+        let b = |k: DefaultKey| &world.beings_and_models[k].0;
+        assert!(b(victim).damage_taken > b(rammer).damage_taken);
+        assert_eq!(b(victim).damage_taken, -b(victim).energy_update);
+        assert_eq!(b(bystander).damage_taken, 0.);
+
+        // the channel is cleared once perceived
+        world.perform_being_outputs();
+        assert_eq!(world.beings_and_models[victim].0.damage_taken, 0.);
+    }
+
+    #[test]
+    fn only_a_fresh_cell_pays_the_exploration_bonus() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        let b = &mut world.beings_and_models[k].0;
+        let (start, next) = (b.cell, (b.cell.0 + 1, b.cell.1));
+
+        b.enter_cell(next, 0.1);
+        assert_eq!(b.energy_update, 0.1);
+        b.enter_cell(start, 0.1);
+        b.enter_cell(next, 0.1);
+        assert_eq!(b.energy_update, 0.1);
+
+        // once enough other cells have pushed it out of memory, it's fresh again
+        for n in 0..EXPLORATION_MEMORY {
+            b.enter_cell((0, n), 0.);
+        }
+        b.enter_cell(start, 0.1);
+        assert_eq!(b.energy_update, 0.2);
+    }
+
+    #[test]
+    fn a_charge_does_more_damage_than_standing_contact() {
+        let (charge, standstill) = (1., 0.);
+        for alignment in [1., -1.] {
+            assert!(
+                collision_damage(alignment, charge, 1.)
+                    > collision_damage(alignment, standstill, 1.)
+            );
+            assert_eq!(collision_damage(alignment, standstill, 1.), 0.);
+            assert_eq!(
+                collision_damage(alignment, charge, 0.),
+                collision_damage(alignment, standstill, 0.)
+            );
+        }
+        assert_eq!(collision_damage(-1., 0.5, 0.5), 0.75 * B_REAR_DAMAGE);
+    }
+
+    #[test]
+    fn a_wall_between_speaker_and_listener_blocks_the_speechlet() {
+        let mut world = World::with_seed(0);
+        let (origin, listener) = (centre(), centre() + Vec2::new(12., 0.));
+        world.add_obstruct(centre() + Vec2::new(6., 0.));
+        world.add_obstruct(centre() + Vec2::new(6., 10.));
+        world.rebuild_cells();
+
+        let blocked = |from, to| line_blocked(&world.obstructs, &world.obstruct_cells, from, to);
+        assert!(blocked(origin, listener));
+        assert!(blocked(listener, origin));
+        assert!(!blocked(origin, centre() + Vec2::new(0., 12.)));
+    }
+
+    #[test]
+    fn a_well_fed_being_can_still_die_of_its_wounds() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        let b = &mut world.beings_and_models[k].0;
+        b.energy = 100. * B_START_ENERGY;
+        b.take_damage_under(B_START_HEALTH / 2., true);
+        world.tire_beings();
+        assert!(world.beings_and_models[k].0.energy > B_START_ENERGY);
+
+        world.beings_and_models[k]
+            .0
+            .take_damage_under(B_START_HEALTH / 2., true);
+        world.tire_beings();
+        assert!(!world.beings_and_models.contains_key(k));
+    }
+
+    #[test]
+    fn a_being_in_mud_moves_slower() {
+        let _turn = backend_turn();
+        let mut forward = [0.; B_OUTPUT_LEN];
+        forward[0] = 1.;
+        let distance_on = |ground: TerrainCell| {
+            let (mut world, k) = lone_being(forward);
+            world.terrain.fill(ground);
+            substeps(&mut world, 1);
+            world.beings_and_models[k].0.pos.distance(centre())
+        };
+
+        let mud = TerrainCell {
+            speed: 0.5,
+            tire: 1.,
+        };
+        let plain = distance_on(TerrainCell::PLAIN);
+        assert!(plain > 0.);
+        assert!((distance_on(mud) - plain / 2.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn crossing_a_stage_boundary_applies_the_next_stage() {
+        let _turn = backend_turn();
+        const CURRICULUM: &[Stage] = &[
+            Stage {
+                generations: 0..1,
+                reworld_threshold: 10,
+                max_food: 40,
+                mutation_rate: 0.01,
+            },
+            Stage {
+                generations: 1..2,
+                reworld_threshold: 2,
+                max_food: 80,
+                mutation_rate: 0.1,
+            },
+        ];
+        assert!(stages_are_contiguous(CURRICULUM));
+
+        let mut world = World::with_seed(0);
+        world.stages = CURRICULUM;
+        let population = (0..5)
+            .map(|_| (0, Model::standard_model(&DEVICE)))
+            .collect();
+        world.populate(population, &[], false);
+        assert!(world.reworld_due(None));
+
+        world.reworld();
+        assert_eq!(world.generation, 1);
+        assert_eq!(world.max_food, 80);
+        // a handful of beings no longer falls under the threshold
+        let culled: Vec<DefaultKey> = world.beings_and_models.keys().skip(5).collect();
+        for k in culled {
+            world.beings_and_models.remove(k);
+        }
+        assert!(!world.reworld_due(None));
+    }
+
+    #[test]
+    fn a_moving_beings_trajectory_fills_up_to_its_cap() {
+        let _turn = backend_turn();
+        let mut output = [0.; B_OUTPUT_LEN];
+        output[0] = 0.5;
+        let (mut world, k) = lone_being(output);
+        let start = world.beings_and_models[k].0.pos;
+
+        for step in 1..=6 {
+            substeps(&mut world, 1);
+            world.beings_and_models[k].0.age += 1;
+            world.record_trajectories_under(4, 2);
+
+            assert_eq!(world.trajectory(k).unwrap().len(), (step / 2).min(4));
+        }
+        for _ in 0..6 {
+            substeps(&mut world, 1);
+            world.beings_and_models[k].0.age += 1;
+            world.record_trajectories_under(4, 2);
+        }
+
+        let history = world.trajectory(k).unwrap();
+        assert_eq!(history.len(), 4);
+        // six were recorded and the two oldest dropped, so what's left runs oldest first up to where it is now
+        let distances: Vec<f32> = history.iter().map(|p| p.distance(start)).collect();
+        assert!(distances.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(*history.back().unwrap(), world.beings_and_models[k].0.pos);
+    }
+
+    #[test]
+    fn a_laid_pheromone_fades_and_is_sensed_by_a_passing_being() {
+        let _turn = backend_turn();
+        let mut output = [0.; B_OUTPUT_LEN];
+        output[0] = 1.;
+        let (mut world, k) = lone_being(output);
+        let (i, j) = world.beings_and_models[k].0.cell;
+        let ahead = two_to_one((i + 1, j));
+        world.pheromone[ahead] += PHEROMONE_DEPOSIT;
+
+        for _ in 0..10 {
+            world.decay_pheromone();
+        }
+        let left = PHEROMONE_DEPOSIT * PHEROMONE_DECAY.powi(10);
+        assert!((world.pheromone[ahead] - left).abs() < 1e-6);
+
+        // from the next cell over the mark is ahead of it, and behind it once it turns around
+        let [here, forward, sideways] = pheromone_sense(&world.pheromone, (i, j), 0.);
+        assert_eq!(here, 0.);
+        assert!((forward - left / 2.).abs() < 1e-6);
+        assert!(sideways.abs() < 1e-6);
+        assert!(pheromone_sense(&world.pheromone, (i, j), PI)[1] < 0.);
+
+        while world.beings_and_models[k].0.cell == (i, j) {
+            substeps(&mut world, 1);
+        }
+        let b = &world.beings_and_models[k].0;
+        assert_eq!(b.cell, (i + 1, j));
+        assert_eq!(
+            pheromone_sense(&world.pheromone, b.cell, b.rotation)[0],
+            world.pheromone[ahead]
+        );
+    }
+
+    // the rows a lone being at the centre would see of one food at `offset` from it
+    fn food_rows(world: &mut World, k: DefaultKey, offset: Vec2) -> Vec<Vec<f32>> {
+        world.foods.clear();
+        world.add_food(centre() + offset, F_VALUE_DIST.max(), false);
+        let food = world.foods.values().next().unwrap();
+        vec![Vec::from(
+            b_collides_f(&world.beings_and_models[k].0, food).1,
+        )]
+    }
+
+    #[test]
+    fn food_dead_ahead_changes_the_answer_without_advancing_the_world() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        let ahead = food_rows(&mut world, k, Vec2::new(B_FOV_PX / 2., 0.));
+        let behind = food_rows(&mut world, k, Vec2::new(-B_FOV_PX / 2., 0.));
+        let state = |world: &World| {
+            let b = &world.beings_and_models[k].0;
+            (b.pos, b.energy, b.age)
+        };
+        let before = state(&world);
+
+        let alone = world.debug_forward(k, vec![], vec![], vec![]).unwrap();
+        let sees_ahead = world
+            .debug_forward(k, vec![], ahead.clone(), vec![])
+            .unwrap();
+        let sees_behind = world.debug_forward(k, vec![], behind, vec![]).unwrap();
+
+        assert_ne!(sees_ahead, alone);
+        assert_ne!(sees_ahead, sees_behind);
+        assert_eq!(
+            sees_ahead,
+            world.debug_forward(k, vec![], ahead, vec![]).unwrap()
+        );
+        assert_eq!(state(&world), before);
+    }
+
+    // however crowded the scene, every output comes out of the final tanh
+    #[test]
+    fn standard_model_outputs_stay_within_tanh_range() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        let widths = world.schema.widths();
+        let foods: Vec<Vec<f32>> = (0..8)
+            .flat_map(|n| food_rows(&mut world, k, Vec2::from_angle(n as f32) * B_FOV_PX / 3.))
+            .collect();
+        let beings = vec![vec![0.5; widths[0]]; 8];
+        let speechlets = vec![vec![-1.; widths[2]]; 8];
+
+        for (being, fo, speechlet) in [
+            (vec![], foods.clone(), vec![]),
+            (beings.clone(), vec![], vec![]),
+            (beings, foods, speechlets),
+        ] {
+            let output = world.debug_forward(k, being, fo, speechlet).unwrap();
+            assert!(output.iter().all(|x| (-1. ..=1.).contains(x)));
+        }
+    }
+
+    // a small arena fills up: every spot handed out keeps its distance, and once there's no room left the
+    // retries run out rather than stacking
+    #[test]
+    fn spawned_foods_keep_their_separation() {
+        let _turn = backend_turn();
+        let mut world = World::with_rng(StdRng::seed_from_u64(0));
+        world.bounds = (Vec2::ZERO, Vec2::splat(200.));
+        let separation = 40.;
+
+        let mut refused = 0;
+        for _ in 0..200 {
+            match world.sample_food_spot_under(0., separation) {
+                Some(pos) => world.add_food(pos, F_VALUE_DIST.max(), false),
+                None => refused += 1,
+            }
+        }
+
+        let spots: Vec<Vec2> = world.foods.values().map(|f| f.pos).collect();
+        for (n, a) in spots.iter().enumerate() {
+            assert!(spots[n + 1..].iter().all(|b| a.distance(*b) >= separation));
+        }
+        assert!(refused > 0);
+        assert!(spots.len() > 10, "{}", spots.len());
+    }
+
+    #[test]
+    fn mining_an_obstruct_pays_out_until_it_crumbles() {
+        let _turn = backend_turn();
+        let mut world = World::with_rng(StdRng::seed_from_u64(0));
+        world.add_obstruct(centre());
+        let k = world.obstructs.keys().next().unwrap();
+
+        let mut energy = 0.;
+        let mut steps = 0;
+        while world.obstructs.contains_key(k) {
+            let before = world.obstructs[k].age;
+            let gained = mine(&mut world.obstructs[k], 1., 1.);
+            assert!(gained > 0.);
+            assert_eq!(gained, (before - world.obstructs[k].age) * MINE_YIELD);
+            energy += gained;
+            world.age_obstructs();
+            steps += 1;
+        }
+
+        assert!(steps <= (O_START_HEALTH / MINE_RATE).ceil() as usize);
+        assert!(energy > 0.);
+        assert!(energy <= SPAWN_O_RATIO * B_START_ENERGY + 1e-3);
+    }
+
+    // founders go round-robin, so every third one shares a centre and lies within `radius` of it
+    #[test]
+    fn clustered_founders_stay_within_their_radius() {
+        let bounds = World::with_rng(StdRng::seed_from_u64(0)).bounds;
+        let (n, radius) = (3, 30.);
+        let mut rng = StdRng::seed_from_u64(5);
+        let placed = BeingSpawn::Clusters { n, radius }.positions(30, bounds, &mut rng);
+        assert_eq!(placed.len(), 30);
+
+        for cluster in 0..n {
+            let members: Vec<Vec2> = placed.iter().skip(cluster).step_by(n).copied().collect();
+            for a in &members {
+                assert!(members.iter().all(|b| a.distance(*b) <= 2. * radius));
+            }
+        }
+        for (i, a) in placed.iter().enumerate() {
+            assert!(a.cmpge(bounds.0 + B_RADIUS).all() && a.cmple(bounds.1 - B_RADIUS).all());
+            assert!(placed[i + 1..]
+                .iter()
+                .all(|b| a.distance(*b) >= 2. * B_RADIUS));
+        }
+    }
+
+    // the value SENSE_POPULATION appends to every own row
+    #[test]
+    fn the_population_channel_follows_the_head_count() {
+        let _turn = backend_turn();
+        let mut world = World::with_rng(StdRng::seed_from_u64(0));
+        assert_eq!(world.crowding(), 0.);
+
+        let keys: Vec<DefaultKey> = (0..B_START_COUNT)
+            .map(|n| add_plain_being(&mut world, Vec2::splat(10. + n as f32)))
+            .collect();
+        assert_eq!(world.crowding(), 1.);
+
+        for k in &keys[..B_START_COUNT / 2] {
+            world.beings_and_models.remove(*k);
+        }
+        assert_eq!(world.crowding(), 0.5);
+
+        for n in 0..B_START_COUNT {
+            add_plain_being(&mut world, Vec2::new(10. + n as f32, 20.));
+        }
+        assert_eq!(world.crowding(), 1.5);
+    }
+
+    #[test]
+    fn a_corpse_is_fed_on_over_several_steps() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        world.add_corpse(centre() + Vec2::new(C_RADIUS, 0.));
+        let c = world.corpses.keys().next().unwrap();
+
+        let mut fed = 0.;
+        let mut steps = 0;
+        while world.corpses.contains_key(c) {
+            let before = world.corpses[c].energy;
+            world.check_collisions(1);
+            let bite = std::mem::take(&mut world.beings_and_models[k].0.energy_update);
+            assert!(bite > 0. && bite <= C_FEED_RATE);
+            assert_eq!(world.corpses[c].energy, before - bite);
+            fed += bite;
+            world.age_corpses();
+            steps += 1;
+        }
+
+        // one bite at a time, with rot taking a little of what's left each step
+        assert!(steps > 1);
+        assert!(steps <= (C_START_ENERGY / C_FEED_RATE).ceil() as usize);
+        assert!(fed > C_START_ENERGY * 0.9 && fed <= C_START_ENERGY);
+    }
+
+    // an obstruct doesn't give way, so one resolution takes the being all the way out rather than half
+    #[test]
+    fn a_being_pressed_into_an_obstruct_is_fully_separated() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        let contact = B_RADIUS + O_RADIUS;
+        world.add_obstruct(centre() + Vec2::new(contact - 1.5, 0.));
+        let o = world.obstructs.values().next().unwrap().pos;
+
+        world.check_collisions(1);
+        world.update_cells();
+
+        let gap = world.beings_and_models[k].0.pos.distance(o);
+        assert!((gap - contact).abs() < 1e-4, "{} vs {}", gap, contact);
+    }
+
+    // as same_seed_worlds_step_identically_across_a_reworld, with one world kept off the thread pool
+    #[test]
+    fn serial_and_rayon_worlds_step_identically() {
+        let _turn = backend_turn();
+        let mut worlds = [World::new_seeded(3), World::new_seeded(3)];
+        worlds[0].set_parallelism(Parallelism::Serial);
+        worlds[1].set_parallelism(Parallelism::Rayon);
+        let mut traces = [vec![], vec![]];
+        for _ in 0..8 {
+            for (world, trace) in worlds.iter_mut().zip(&mut traces) {
+                world.step(1);
+                trace.push(world.state_hashes());
+            }
+        }
+
+        assert_eq!(trace::first_divergence(&traces[0], &traces[1]), None);
+    }
+
+    #[test]
+    fn grid_placed_obstructs_land_on_grid_nodes() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        let cell = 10.;
+        let node = (centre() / cell).round() * cell + Vec2::new(3. * cell, 0.);
+        let spawns = [
+            node + Vec2::new(2., -3.),
+            node + Vec2::new(cell - 1., 4.),
+            node + Vec2::new(-2., cell + 2.),
+            // snaps onto the first one's node, which is already taken
+            node + Vec2::new(-1., 1.),
+        ];
+
+        world.place_obstructs_under(
+            spawns.map(|pos| (pos, k)).to_vec(),
+            ObstructPlacement::Grid { cell },
+        );
+
+        let mut landed: Vec<Vec2> = world.obstructs.values().map(|o| o.pos).collect();
+        landed.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        assert_eq!(
+            landed,
+            vec![node, node + Vec2::new(0., cell), node + Vec2::new(cell, 0.)]
+        );
+        assert_eq!(
+            world.beings_and_models[k].0.energy_update,
+            SPAWN_O_RATIO * B_START_ENERGY
+        );
+    }
+
+    #[test]
+    fn a_growing_being_reaches_further_and_tires_faster() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        world.add_obstruct(centre() + Vec2::new(B_RADIUS + O_RADIUS + 1., 0.));
+        let o = world.obstructs.values().next().unwrap();
+
+        let b = &mut world.beings_and_models[k].0;
+        assert!(b_collides_o(b, o).0 < 0.);
+        let upkeep = b.upkeep_scale(true);
+
+        for _ in 0..100 {
+            b.resize(1.);
+        }
+        assert_eq!(b.radius, B_MAX_RADIUS);
+        assert!((b.energy_update + (B_MAX_RADIUS - B_RADIUS) * B_RESIZE_COST).abs() < 1e-5);
+        assert!(b_collides_o(b, o).0 > 0.);
+        assert_eq!(b.upkeep_scale(true), 4. * upkeep);
+        assert_eq!(b.upkeep_scale(false), 1.);
+    }
+
+    #[test]
+    fn offspring_land_within_reach_of_their_parent() {
+        let _turn = backend_turn();
+        let mut world = World::with_seed(2);
+        let radius = 12.;
+        let parents = [centre(), Vec2::splat(40.), centre() + Vec2::new(100., -60.)];
+        let near: Vec<Option<Vec2>> = (0..30).map(|n| Some(parents[n % 3])).collect();
+        let population = vec![(0, Model::standard_model(&DEVICE)); near.len()];
+
+        let keys = world.populate_under(population, &near, false, Some(radius));
+
+        for (k, parent) in keys.iter().zip(&near) {
+            let pos = world.beings_and_models[*k].0.pos;
+            assert!(
+                pos.distance(parent.unwrap()) <= radius,
+                "{} from {}",
+                pos,
+                parent.unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn the_nearest_wall_is_sensed_at_edges_and_corners() {
+        let size = 10. * B_FOV_PX;
+        let bounds = (Vec2::ZERO, Vec2::splat(size));
+        let close = B_FOV_PX / 4.;
+        let close_enough = |[dist, bearing]: [f32; 2], expected: [f32; 2]| {
+            assert!(
+                (dist - expected[0]).abs() < 1e-5,
+                "distance {} vs {}",
+                dist,
+                expected[0]
+            );
+            assert!(
+                (bearing.abs() - expected[1].abs()).abs() < 1e-5,
+                "bearing {} vs {}",
+                bearing,
+                expected[1]
+            );
+        };
+
+        // out of sight of every wall the distance caps out, and the four-way tie goes to the left one
+        close_enough(nearest_wall(Vec2::splat(size / 2.), 0., bounds), [1., 1.]);
+
+        // the left edge, faced, turned from and side on
+        let left = Vec2::new(close, size / 2.);
+        close_enough(nearest_wall(left, PI, bounds), [0.25, 0.]);
+        close_enough(nearest_wall(left, 0., bounds), [0.25, 1.]);
+        assert!((nearest_wall(left, PI / 2., bounds)[1] - 0.5).abs() < 1e-5);
+        assert!((nearest_wall(left, -PI / 2., bounds)[1] + 0.5).abs() < 1e-5);
+
+        // the far edges are as near as the near ones
+        close_enough(
+            nearest_wall(Vec2::new(size - close, size / 2.), 0., bounds),
+            [0.25, 0.],
+        );
+        close_enough(
+            nearest_wall(Vec2::new(size / 2., size - close), PI / 2., bounds),
+            [0.25, 0.],
+        );
+
+        // in a corner whichever wall is closer wins, and the bearing turns with it
+        let corner = Vec2::new(close, close / 2.);
+        close_enough(nearest_wall(corner, -PI / 2., bounds), [0.125, 0.]);
+        let corner = Vec2::new(size - close / 2., size - close);
+        close_enough(nearest_wall(corner, 0., bounds), [0.125, 0.]);
+        close_enough(nearest_wall(corner, PI / 2., bounds), [0.125, 0.5]);
+
+        assert_eq!(
+            WallSense::Nearest.sense(corner, 0., bounds).len(),
+            WallSense::Nearest.channels().len()
+        );
+    }
+
+    // the inputs are cleared between steps, as they are once the model has run on them
+    #[test]
+    fn a_heard_speechlet_lingers_for_the_retention_window() {
+        let _turn = backend_turn();
+        let (mut world, k) = lone_being([0.; B_OUTPUT_LEN]);
+        let b = &mut world.beings_and_models[k].0;
+        let heard = vec![0.8, -0.4, 1.];
+        let retention = 3;
+
+        b.speechlet_inputs.push(heard.clone());
+        b.replay_last_heard(retention);
+        assert_eq!(b.speechlet_inputs, vec![heard.clone()]);
+
+        for step in 1..=retention {
+            b.speechlet_inputs.clear();
+            b.speechlet_weights.clear();
+            b.replay_last_heard(retention);
+
+            let fade = 1. - step as f32 / (retention + 1) as f32;
+            let expected: Vec<f32> = heard.iter().map(|x| x * fade).collect();
+            assert_eq!(b.speechlet_inputs, vec![expected]);
+        }
+
+        b.speechlet_inputs.clear();
+        b.replay_last_heard(retention);
+        assert!(b.speechlet_inputs.is_empty());
+    }
+
+    #[test]
+    fn spawned_plants_follow_the_value_distribution() {
+        let spawned = |dist: FoodValueDist| -> Vec<f32> {
+            let mut world = World::with_rng(StdRng::seed_from_u64(0));
+            world.max_food = 4000;
+            while world.foods.len() < 2000 {
+                world.repop_foods_under(FoodCapMode::All, dist);
+            }
+            world.foods.values().map(|f| f.val).collect()
+        };
+        let mean = |vals: &[f32]| vals.iter().sum::<f32>() / vals.len() as f32;
+
+        assert!(spawned(FoodValueDist::Fixed(3.))
+            .iter()
+            .all(|val| *val == 3.));
+
+        let uniform = spawned(FoodValueDist::Uniform { min: 1., max: 5. });
+        assert!(uniform.iter().all(|val| (1. ..=5.).contains(val)));
+        assert!((mean(&uniform) - 3.).abs() < 0.1, "{}", mean(&uniform));
+
+        let bimodal = spawned(FoodValueDist::Bimodal {
+            low: 1.,
+            high: 5.,
+            p_high: 0.25,
+        });
+        assert!(bimodal.iter().all(|val| *val == 1. || *val == 5.));
+        let high_share =
+            bimodal.iter().filter(|val| **val == 5.).count() as f32 / bimodal.len() as f32;
+        assert!((high_share - 0.25).abs() < 0.03, "{}", high_share);
+    }
 }
@@ -3,7 +3,7 @@ use std::iter::zip;
 use burn::{
     module::Module,
     nn::{
-        attention::{MhaInput, MultiHeadAttention, MultiHeadAttentionConfig},
+        attention::{MultiHeadAttention, MultiHeadAttentionConfig},
         Linear, LinearConfig, Lstm, LstmConfig,
     },
     prelude::Backend,
@@ -11,7 +11,7 @@ use burn::{
 };
 
 use crate::{
-    being_nn::{combine_linears, combine_lstms, combine_mhas, Activation, Tanh, FF}, splice_ffs, B_OUTPUT_LEN, GENOME_LEN, SPEECHLET_LEN
+    being_nn::{attend_set, combine_linears, combine_lstms, combine_mhas, describe_model, merge_branches, pool_set, sense_branch, Activation, CrossoverGranularity, MutationProfile, Tanh, FF}, schema::SensorySchema, splice_ffs, B_OUTPUT_LEN, CROSSOVER_GRANULARITY, MHA_USE_ATTENTION, MUTATION_PROFILE, SENSES
 };
 
 #[derive(Clone)]
@@ -31,6 +31,7 @@ pub struct MhaModel<B: Backend> {
     pub intermediate_dim: usize,
     pub num_heads: usize,
     pub inp_sizes: (usize, usize, usize),
+    pub use_attention: bool, // when false the mhas are skipped, and neither crossed over nor mutated
 }
 
 impl<B: Backend> MhaModel<B> {
@@ -40,15 +41,14 @@ impl<B: Backend> MhaModel<B> {
         speechlet_config: (usize, usize, Activation),
         self_config: (Vec<usize>, Vec<Activation>),
         final_config: (Vec<usize>, Vec<Activation>),
-
-        concat_before_final: bool,
-        num_heads: usize,
+        mixing_config: (bool, usize, bool), // (concat_before_final, num_heads, use_attention)
 
         device: &Device<B>,
     ) -> Self {
         let (being_inp_size, being_out_size, being_act) = being_config;
         let (fo_inp_size, fo_out_size, fo_act) = fo_config;
         let (speechlet_inp_size, speechlet_out_size, speechlet_act) = speechlet_config;
+        let (concat_before_final, num_heads, use_attention) = mixing_config;
 
         let intermediate_dim: usize;
 
@@ -106,15 +106,18 @@ impl<B: Backend> MhaModel<B> {
             intermediate_dim: intermediate_dim,
             num_heads: num_heads,
             inp_sizes: (being_inp_size, fo_inp_size, speechlet_inp_size),
+            use_attention: use_attention,
         }
     }
 
     pub fn standard_model(device: &Device<B>) -> Self {
-        let being_config = (3 + GENOME_LEN, 8, Activation::Tanh(Tanh {}));
-        let fo_config = (5, 8, Activation::Tanh(Tanh {}));
-        let speechlet_config = (SPEECHLET_LEN, 8, Activation::Tanh(Tanh {}));
+        let [being_width, fo_width, speechlet_width, own_width] = SensorySchema::standard().widths();
+
+        let being_config = (being_width, 8, Activation::Tanh(Tanh {}));
+        let fo_config = (fo_width, 8, Activation::Tanh(Tanh {}));
+        let speechlet_config = (speechlet_width, 8, Activation::Tanh(Tanh {}));
         let self_config = (
-            vec![5, 8],
+            vec![own_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let final_config = (
@@ -127,12 +130,72 @@ impl<B: Backend> MhaModel<B> {
             speechlet_config,
             self_config,
             final_config,
-            true,
-            1,
+            (true, 1, MHA_USE_ATTENTION),
             device,
         );
     }
 
+    // the row width each sensory branch takes, in SensorySchema::widths order
+    pub fn input_widths(&self) -> [usize; 4] {
+        [
+            self.inp_sizes.0,
+            self.inp_sizes.1,
+            self.inp_sizes.2,
+            self.self_model.config.0[0],
+        ]
+    }
+
+    // every weight and bias the model carries, across all of its branches
+    pub fn num_params(&self) -> usize {
+        [
+            &self.being_model,
+            &self.fo_model,
+            &self.speechlet_model,
+            &self.self_model,
+            &self.final_model,
+        ]
+        .iter()
+        .map(|ff| ff.num_params())
+        .sum::<usize>()
+            + self.being_mha.num_params()
+            + self.fo_mha.num_params()
+            + self.speechlet_mha.num_params()
+    }
+
+    // see describe_model
+    pub fn architecture(&self) -> String {
+        describe_model(
+            "MhaModel",
+            self.concat_before_final,
+            self.intermediate_dim,
+            [
+                &self.being_model,
+                &self.fo_model,
+                &self.speechlet_model,
+                &self.self_model,
+                &self.final_model,
+            ],
+            &[
+                (
+                    "being_mha",
+                    format!("{} wide, {} heads", self.inp_sizes.0, self.num_heads),
+                    self.being_mha.num_params(),
+                ),
+                (
+                    "fo_mha",
+                    format!("{} wide, {} heads", self.inp_sizes.1, self.num_heads),
+                    self.fo_mha.num_params(),
+                ),
+                (
+                    "speechlet_mha",
+                    format!("{} wide, {} heads", self.inp_sizes.2, self.num_heads),
+                    self.speechlet_mha.num_params(),
+                ),
+            ],
+            self.num_params(),
+        )
+    }
+
     pub fn forward(
         &mut self,
         being_tensor: Tensor<B, 2>,
@@ -140,58 +203,31 @@ impl<B: Backend> MhaModel<B> {
         speechlet_tensor: Tensor<B, 2>,
         self_tensor: Tensor<B, 2>,
     ) -> Tensor<B, 1> {
-        let (being_tensor, fo_tensor, speechlet_tensor) = (
-            being_tensor.unsqueeze(),
-            fo_tensor.unsqueeze(),
-            speechlet_tensor.unsqueeze(),
-        );
-
-        let beings_output = self
-            .being_mha
-            .forward(MhaInput::new(
-                being_tensor.clone(),
-                being_tensor.clone(),
-                being_tensor,
-            ))
-            .context
-            .squeeze(0);
-        let beings_output = self.being_model.forward(beings_output).mean_dim(0);
-
-        let fo_output = self
-            .fo_mha
-            .forward(MhaInput::new(
-                fo_tensor.clone(),
-                fo_tensor.clone(),
-                fo_tensor.clone(),
-            ))
-            .context
-            .squeeze(0);
-        let fo_output = self.fo_model.forward(fo_output).mean_dim(0);
-
-        let speechlet_output = self
-            .speechlet_mha
-            .forward(MhaInput::new(
-                speechlet_tensor.clone(),
-                speechlet_tensor.clone(),
-                speechlet_tensor.clone(),
-            ))
-            .context
-            .squeeze(0);
-        let speechlet_output = self.speechlet_model.forward(speechlet_output).mean_dim(0);
-
-        let self_output = self.self_model.forward(self_tensor);
-
-        let intermediate: Tensor<B, 2> = {
-            if self.concat_before_final {
-                Tensor::cat(
-                    vec![beings_output, fo_output, speechlet_output, self_output],
-                    1,
-                )
-            } else {
-                (beings_output + fo_output + speechlet_output + self_output) / 4.
+        // without attention, a branch's rows go straight into its FF, just as in SumFxModel
+        let use_attention = self.use_attention;
+        let attend = |mha: &MultiHeadAttention<B>, rows: Tensor<B, 2>| -> Tensor<B, 2> {
+            if !use_attention {
+                return rows;
             }
+            attend_set(mha, rows)
         };
 
+        let beings_output = sense_branch(SENSES.being, &self.being_model, being_tensor, |x| {
+            pool_set(self.being_model.forward(attend(&self.being_mha, x)))
+        });
+        let fo_output = sense_branch(SENSES.food_obstruct, &self.fo_model, fo_tensor, |x| {
+            pool_set(self.fo_model.forward(attend(&self.fo_mha, x)))
+        });
+        let speechlet_output = sense_branch(SENSES.speechlet, &self.speechlet_model, speechlet_tensor, |x| {
+            pool_set(self.speechlet_model.forward(attend(&self.speechlet_mha, x)))
+        });
+        let self_output = sense_branch(SENSES.own, &self.self_model, self_tensor, |x| self.self_model.forward(x));
+
+        let intermediate = merge_branches(
+            [beings_output, fo_output, speechlet_output, self_output],
+            self.concat_before_final,
+        );
+
         let final_output = self.final_model.forward(intermediate).squeeze(0);
         let final_output = activation::tanh(final_output);
 
@@ -203,47 +239,62 @@ impl<B: Backend> MhaModel<B> {
             self.being_model,
             other.being_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let fo_model = splice_ffs(
             self.fo_model,
             other.fo_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let speechlet_model = splice_ffs(
             self.speechlet_model,
             other.speechlet_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let self_model = splice_ffs(
             self.self_model,
             other.self_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let final_model = splice_ffs(
             self.final_model,
             other.final_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
 
+        let (being_mha, fo_mha, speechlet_mha) = if self.use_attention {
+            (
+                combine_mhas(
+                    self.being_mha,
+                    other.being_mha,
+                    crossover_weight,
+                    1. - crossover_weight,
+                ),
+                combine_mhas(
+                    self.fo_mha,
+                    other.fo_mha,
+                    crossover_weight,
+                    1. - crossover_weight,
+                ),
+                combine_mhas(
+                    self.speechlet_mha,
+                    other.speechlet_mha,
+                    crossover_weight,
+                    1. - crossover_weight,
+                ),
+            )
+        } else {
+            (self.being_mha, self.fo_mha, self.speechlet_mha)
+        };
+
         return MhaModel {
-            being_mha: combine_mhas(
-                self.being_mha,
-                other.being_mha,
-                crossover_weight,
-                1. - crossover_weight,
-            ),
-            fo_mha: combine_mhas(
-                self.fo_mha,
-                other.fo_mha,
-                crossover_weight,
-                1. - crossover_weight,
-            ),
-            speechlet_mha: combine_mhas(
-                self.speechlet_mha,
-                other.speechlet_mha,
-                crossover_weight,
-                1. - crossover_weight,
-            ),
+            being_mha: being_mha,
+            fo_mha: fo_mha,
+            speechlet_mha: speechlet_mha,
 
             being_model: being_model,
             fo_model: fo_model,
@@ -255,31 +306,50 @@ impl<B: Backend> MhaModel<B> {
             intermediate_dim: self.intermediate_dim,
             num_heads: self.num_heads,
             inp_sizes: self.inp_sizes,
+            use_attention: self.use_attention,
         };
     }
     
     pub fn mutate(self, mutation_rate: f32, device: &Device<B>) -> Self {
+        self.mutate_under(mutation_rate, MUTATION_PROFILE, device)
+    }
+
+    // as mutate, with the rate scaled per branch by `profile`
+    pub fn mutate_under(self, mutation_rate: f32, profile: MutationProfile, device: &Device<B>) -> Self {
         let mut new_models: Vec<FF<B>> = vec![];
 
-        for model in [
-            self.being_model,
-            self.fo_model,
-            self.speechlet_model,
-            self.self_model,
-            self.final_model,
-        ] {
+        for (model, scale) in zip(
+            [
+                self.being_model,
+                self.fo_model,
+                self.speechlet_model,
+                self.self_model,
+                self.final_model,
+            ],
+            profile.ff_scales(),
+        ) {
             let config = model.config.clone();
             let mutation_model = FF::new(config.0, config.1, device);
-            let new_model = splice_ffs(model, mutation_model, 1. - mutation_rate);
+            let new_model = splice_ffs(model, mutation_model, 1. - mutation_rate * scale, CrossoverGranularity::Weight);
             new_models.push(new_model);
         }
 
-        let being_mutation =
-            MultiHeadAttentionConfig::new(self.inp_sizes.0, self.num_heads).init(device);
-        let fo_mutation =
-            MultiHeadAttentionConfig::new(self.inp_sizes.1, self.num_heads).init(device);
-        let speechlet_mutation =
-            MultiHeadAttentionConfig::new(self.inp_sizes.2, self.num_heads).init(device);
+        let (being_mha, fo_mha, speechlet_mha) = if self.use_attention {
+            let being_mutation =
+                MultiHeadAttentionConfig::new(self.inp_sizes.0, self.num_heads).init(device);
+            let fo_mutation =
+                MultiHeadAttentionConfig::new(self.inp_sizes.1, self.num_heads).init(device);
+            let speechlet_mutation =
+                MultiHeadAttentionConfig::new(self.inp_sizes.2, self.num_heads).init(device);
+
+            (
+                combine_mhas(self.being_mha, being_mutation, 1., mutation_rate * profile.attention),
+                combine_mhas(self.fo_mha, fo_mutation, 1., mutation_rate * profile.attention),
+                combine_mhas(self.speechlet_mha, speechlet_mutation, 1., mutation_rate * profile.attention),
+            )
+        } else {
+            (self.being_mha, self.fo_mha, self.speechlet_mha)
+        };
 
         return MhaModel {
             self_model: new_models[3].to_owned(),
@@ -288,9 +358,9 @@ impl<B: Backend> MhaModel<B> {
             concat_before_final: self.concat_before_final,
             intermediate_dim: self.intermediate_dim,
 
-            being_mha: combine_mhas(self.being_mha, being_mutation, 1., mutation_rate),
-            fo_mha: combine_mhas(self.fo_mha, fo_mutation, 1., mutation_rate),
-            speechlet_mha: combine_mhas(self.speechlet_mha, speechlet_mutation, 1., mutation_rate),
+            being_mha: being_mha,
+            fo_mha: fo_mha,
+            speechlet_mha: speechlet_mha,
 
             being_model: new_models[0].to_owned(),
             fo_model: new_models[1].to_owned(),
@@ -298,6 +368,7 @@ impl<B: Backend> MhaModel<B> {
 
             num_heads: self.num_heads,
             inp_sizes: self.inp_sizes,
+            use_attention: self.use_attention,
         };
     }
 }
@@ -3,7 +3,7 @@ use std::iter::zip;
 use burn::{
     module::Module,
     nn::{
-        attention::{MhaInput, MultiHeadAttention, MultiHeadAttentionConfig},
+        attention::{MultiHeadAttention, MultiHeadAttentionConfig},
         Linear, LinearConfig, Lstm, LstmConfig,
     },
     prelude::Backend,
@@ -11,7 +11,7 @@ use burn::{
 };
 
 use crate::{
-    being_nn::{combine_linears, combine_lstms, combine_mhas, Activation, Tanh, FF}, splice_ffs, B_OUTPUT_LEN, GENOME_LEN, SPEECHLET_LEN
+    being_nn::{attend_set, combine_linears, combine_lstms, combine_mhas, describe_model, merge_branches, pool_set, sense_branch, Activation, CrossoverGranularity, MutationProfile, Tanh, FF}, schema::SensorySchema, splice_ffs, B_OUTPUT_LEN, CROSSOVER_GRANULARITY, LSTM_STATE_DECAY, MUTATION_PROFILE, SENSES
 };
 
 #[derive(Clone)]
@@ -32,8 +32,9 @@ pub struct MhaLstmModel<B: Backend> {
     pub intermediate_dim: usize,
     pub num_heads: usize,
     pub lstm_inp_size: usize,
+    pub state_decay: f32, // gives memory a half-life of ln(0.5) / ln(state_decay) steps
     pub inp_sizes: (usize, usize, usize),
-    state: (Tensor<B, 2>, Tensor<B, 2>),
+    pub(super) state: (Tensor<B, 2>, Tensor<B, 2>),
 }
 
 impl<B: Backend> MhaLstmModel<B> {
@@ -43,15 +44,14 @@ impl<B: Backend> MhaLstmModel<B> {
         speechlet_config: (usize, usize, Activation),
         self_config: (Vec<usize>, Vec<Activation>),
         final_config: (Vec<usize>, Vec<Activation>),
-
-        concat_before_final: bool,
-        num_heads: usize,
+        mixing_config: (bool, usize, f32), // (concat_before_final, num_heads, state_decay)
 
         device: &Device<B>,
     ) -> Self {
         let (being_inp_size, being_out_size, being_act) = being_config;
         let (fo_inp_size, fo_out_size, fo_act) = fo_config;
         let (speechlet_inp_size, speechlet_out_size, speechlet_act) = speechlet_config;
+        let (concat_before_final, num_heads, state_decay) = mixing_config;
 
         let lstm_inp_size = {
             if !concat_before_final {
@@ -119,6 +119,7 @@ impl<B: Backend> MhaLstmModel<B> {
             concat_before_final: concat_before_final,
             intermediate_dim: intermediate_dim,
             lstm_inp_size: lstm_inp_size,
+            state_decay: state_decay,
             state: (
                 Tensor::<B, 2>::zeros([1, intermediate_dim], device).no_grad(),
                 Tensor::<B, 2>::zeros([1, intermediate_dim], device).no_grad(),
@@ -129,11 +130,13 @@ impl<B: Backend> MhaLstmModel<B> {
     }
 
     pub fn standard_model(device: &Device<B>) -> Self {
-        let being_config = (3 + GENOME_LEN, 8, Activation::Tanh(Tanh {}));
-        let fo_config = (5, 8, Activation::Tanh(Tanh {}));
-        let speechlet_config = (SPEECHLET_LEN, 8, Activation::Tanh(Tanh {}));
+        let [being_width, fo_width, speechlet_width, own_width] = SensorySchema::standard().widths();
+
+        let being_config = (being_width, 8, Activation::Tanh(Tanh {}));
+        let fo_config = (fo_width, 8, Activation::Tanh(Tanh {}));
+        let speechlet_config = (speechlet_width, 8, Activation::Tanh(Tanh {}));
         let self_config = (
-            vec![5, 8],
+            vec![own_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let final_config = (
@@ -146,12 +149,78 @@ impl<B: Backend> MhaLstmModel<B> {
             speechlet_config,
             self_config,
             final_config,
-            true,
-            1,
+            (true, 1, LSTM_STATE_DECAY),
             device,
         );
     }
 
+    // the row width each sensory branch takes, in SensorySchema::widths order
+    pub fn input_widths(&self) -> [usize; 4] {
+        [
+            self.inp_sizes.0,
+            self.inp_sizes.1,
+            self.inp_sizes.2,
+            self.self_model.config.0[0],
+        ]
+    }
+
+    // every weight and bias the model carries, across all of its branches
+    pub fn num_params(&self) -> usize {
+        [
+            &self.being_model,
+            &self.fo_model,
+            &self.speechlet_model,
+            &self.self_model,
+            &self.final_model,
+        ]
+        .iter()
+        .map(|ff| ff.num_params())
+        .sum::<usize>()
+            + self.being_mha.num_params()
+            + self.fo_mha.num_params()
+            + self.speechlet_mha.num_params()
+            + self.lstm.num_params()
+    }
+
+    // see describe_model
+    pub fn architecture(&self) -> String {
+        describe_model(
+            "MhaLstmModel",
+            self.concat_before_final,
+            self.intermediate_dim,
+            [
+                &self.being_model,
+                &self.fo_model,
+                &self.speechlet_model,
+                &self.self_model,
+                &self.final_model,
+            ],
+            &[
+                (
+                    "being_mha",
+                    format!("{} wide, {} heads", self.inp_sizes.0, self.num_heads),
+                    self.being_mha.num_params(),
+                ),
+                (
+                    "fo_mha",
+                    format!("{} wide, {} heads", self.inp_sizes.1, self.num_heads),
+                    self.fo_mha.num_params(),
+                ),
+                (
+                    "speechlet_mha",
+                    format!("{} wide, {} heads", self.inp_sizes.2, self.num_heads),
+                    self.speechlet_mha.num_params(),
+                ),
+                (
+                    "lstm",
+                    format!("{} -> {}", self.lstm_inp_size, self.lstm_inp_size),
+                    self.lstm.num_params(),
+                ),
+            ],
+            self.num_params(),
+        )
+    }
+
     pub fn forward(
         &mut self,
         being_tensor: Tensor<B, 2>,
@@ -159,61 +228,29 @@ impl<B: Backend> MhaLstmModel<B> {
         speechlet_tensor: Tensor<B, 2>,
         self_tensor: Tensor<B, 2>,
     ) -> Tensor<B, 1> {
-        let (being_tensor, fo_tensor, speechlet_tensor) = (
-            being_tensor.unsqueeze(),
-            fo_tensor.unsqueeze(),
-            speechlet_tensor.unsqueeze(),
+        let beings_output = sense_branch(SENSES.being, &self.being_model, being_tensor, |x| {
+            pool_set(self.being_model.forward(attend_set(&self.being_mha, x)))
+        });
+        let fo_output = sense_branch(SENSES.food_obstruct, &self.fo_model, fo_tensor, |x| {
+            pool_set(self.fo_model.forward(attend_set(&self.fo_mha, x)))
+        });
+        let speechlet_output = sense_branch(SENSES.speechlet, &self.speechlet_model, speechlet_tensor, |x| {
+            pool_set(self.speechlet_model.forward(attend_set(&self.speechlet_mha, x)))
+        });
+        let self_output = sense_branch(SENSES.own, &self.self_model, self_tensor, |x| self.self_model.forward(x));
+
+        let intermediate = merge_branches(
+            [beings_output, fo_output, speechlet_output, self_output],
+            self.concat_before_final,
         );
 
-        let beings_output = self
-            .being_mha
-            .forward(MhaInput::new(
-                being_tensor.clone(),
-                being_tensor.clone(),
-                being_tensor,
-            ))
-            .context
-            .squeeze(0);
-        let beings_output = self.being_model.forward(beings_output).mean_dim(0);
-
-        let fo_output = self
-            .fo_mha
-            .forward(MhaInput::new(
-                fo_tensor.clone(),
-                fo_tensor.clone(),
-                fo_tensor.clone(),
-            ))
-            .context
-            .squeeze(0);
-        let fo_output = self.fo_model.forward(fo_output).mean_dim(0);
-
-        let speechlet_output = self
-            .speechlet_mha
-            .forward(MhaInput::new(
-                speechlet_tensor.clone(),
-                speechlet_tensor.clone(),
-                speechlet_tensor.clone(),
-            ))
-            .context
-            .squeeze(0);
-        let speechlet_output = self.speechlet_model.forward(speechlet_output).mean_dim(0);
-
-        let self_output = self.self_model.forward(self_tensor);
-
-        let intermediate: Tensor<B, 2> = {
-            if self.concat_before_final {
-                Tensor::cat(
-                    vec![beings_output, fo_output, speechlet_output, self_output],
-                    1,
-                )
-            } else {
-                (beings_output + fo_output + speechlet_output + self_output) / 4.
-            }
-        };
-
+        let state = (
+            self.state.0.clone().mul_scalar(self.state_decay),
+            self.state.1.clone().mul_scalar(self.state_decay),
+        );
         let (c, h) = self
             .lstm
-            .forward(intermediate.clone().unsqueeze(), Some(self.state.clone()));
+            .forward(intermediate.clone().unsqueeze(), Some(state));
 
         let (c, h): (Tensor<B, 2>, Tensor<B, 2>) = (c.squeeze(0).no_grad(), h.squeeze(0).no_grad());
         self.state = (c.clone(), h.clone());
@@ -229,26 +266,31 @@ impl<B: Backend> MhaLstmModel<B> {
             self.being_model,
             other.being_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let fo_model = splice_ffs(
             self.fo_model,
             other.fo_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let speechlet_model = splice_ffs(
             self.speechlet_model,
             other.speechlet_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let self_model = splice_ffs(
             self.self_model,
             other.self_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let final_model = splice_ffs(
             self.final_model,
             other.final_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
 
         return MhaLstmModel {
@@ -287,6 +329,7 @@ impl<B: Backend> MhaLstmModel<B> {
             concat_before_final: self.concat_before_final,
             intermediate_dim: self.intermediate_dim,
             lstm_inp_size: self.lstm_inp_size,
+            state_decay: self.state_decay,
             state: (
                 Tensor::<B, 2>::zeros([1, self.intermediate_dim as usize], device),
                 Tensor::<B, 2>::zeros([1, self.intermediate_dim as usize], device),
@@ -296,18 +339,26 @@ impl<B: Backend> MhaLstmModel<B> {
         };
     }
     pub fn mutate(self, mutation_rate: f32, device: &Device<B>) -> Self {
+        self.mutate_under(mutation_rate, MUTATION_PROFILE, device)
+    }
+
+    // as mutate, with the rate scaled per branch by `profile`
+    pub fn mutate_under(self, mutation_rate: f32, profile: MutationProfile, device: &Device<B>) -> Self {
         let mut new_models: Vec<FF<B>> = vec![];
 
-        for model in [
-            self.being_model,
-            self.fo_model,
-            self.speechlet_model,
-            self.self_model,
-            self.final_model,
-        ] {
+        for (model, scale) in zip(
+            [
+                self.being_model,
+                self.fo_model,
+                self.speechlet_model,
+                self.self_model,
+                self.final_model,
+            ],
+            profile.ff_scales(),
+        ) {
             let config = model.config.clone();
             let mutation_model = FF::new(config.0, config.1, device);
-            let new_model = splice_ffs(model, mutation_model, 1. - mutation_rate);
+            let new_model = splice_ffs(model, mutation_model, 1. - mutation_rate * scale, CrossoverGranularity::Weight);
             new_models.push(new_model);
         }
 
@@ -323,16 +374,17 @@ impl<B: Backend> MhaLstmModel<B> {
 
         return MhaLstmModel {
             self_model: new_models[3].to_owned(),
-            lstm: combine_lstms(self.lstm, mutation_lstm, 1., mutation_rate),
+            lstm: combine_lstms(self.lstm, mutation_lstm, 1., mutation_rate * profile.lstm),
             final_model: new_models[4].to_owned(),
 
             concat_before_final: self.concat_before_final,
             intermediate_dim: self.intermediate_dim,
             lstm_inp_size: self.lstm_inp_size,
+            state_decay: self.state_decay,
 
-            being_mha: combine_mhas(self.being_mha, being_mutation, 1., mutation_rate),
-            fo_mha: combine_mhas(self.fo_mha, fo_mutation, 1., mutation_rate),
-            speechlet_mha: combine_mhas(self.speechlet_mha, speechlet_mutation, 1., mutation_rate),
+            being_mha: combine_mhas(self.being_mha, being_mutation, 1., mutation_rate * profile.attention),
+            fo_mha: combine_mhas(self.fo_mha, fo_mutation, 1., mutation_rate * profile.attention),
+            speechlet_mha: combine_mhas(self.speechlet_mha, speechlet_mutation, 1., mutation_rate * profile.attention),
 
             being_model: new_models[0].to_owned(),
             fo_model: new_models[1].to_owned(),
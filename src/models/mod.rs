@@ -1,4 +1,156 @@
+use burn::tensor::backend::Backend;
+
 pub mod mha;
 pub mod mhalstm;
 pub mod sumfx;
 pub mod sumfxlstm;
+
+// what every being model has in common beyond its inherent methods
+pub trait BeingModel {
+    // forgets whatever a recurrent model carried over from earlier forwards; stateless models have nothing to forget
+    fn reset_state(&mut self) {}
+}
+
+impl<B: Backend> BeingModel for sumfx::SumFxModel<B> {}
+
+impl<B: Backend> BeingModel for mha::MhaModel<B> {}
+
+impl<B: Backend> BeingModel for sumfxlstm::SumFxLstmModel<B> {
+    fn reset_state(&mut self) {
+        self.state = (self.state.0.zeros_like(), self.state.1.zeros_like());
+    }
+}
+
+impl<B: Backend> BeingModel for mhalstm::MhaLstmModel<B> {
+    fn reset_state(&mut self) {
+        self.state = (self.state.0.zeros_like(), self.state.1.zeros_like());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::being_nn::{MutationProfile, Senses};
+    use crate::schema::SensorySchema;
+    use crate::{B_OUTPUT_LEN, BACKEND, DEVICE};
+    use burn::tensor::Tensor;
+
+    fn inputs(widths: [usize; 4]) -> [Tensor<BACKEND, 2>; 4] {
+        let [being, fo, speechlet, own] = widths.map(|w| Tensor::<BACKEND, 2>::ones([1, w], &DEVICE));
+        [being, fo, speechlet, own]
+    }
+
+    fn all_zero(state: &(Tensor<BACKEND, 2>, Tensor<BACKEND, 2>)) -> bool {
+        [&state.0, &state.1].into_iter().all(|t| t.clone().abs().max().into_scalar() == 0.)
+    }
+
+    // what reworld does to every survivor it carries over
+    #[test]
+    fn reset_lstm_models_start_from_zero_state() {
+        let _turn = crate::tests::backend_turn();
+
+        let mut sumfx = sumfxlstm::SumFxLstmModel::<BACKEND>::standard_model(&DEVICE);
+        let [being, fo, speechlet, own] = inputs(sumfx.input_widths());
+        sumfx.forward(being, fo, speechlet, own);
+        assert!(!all_zero(&sumfx.state));
+        sumfx.reset_state();
+        assert!(all_zero(&sumfx.state));
+
+        let mut mha = mhalstm::MhaLstmModel::<BACKEND>::standard_model(&DEVICE);
+        let [being, fo, speechlet, own] = inputs(mha.input_widths());
+        mha.forward(being, fo, speechlet, own);
+        assert!(!all_zero(&mha.state));
+        mha.reset_state();
+        assert!(all_zero(&mha.state));
+    }
+
+
+    // each forward adds less than 1. to any cell, so a cell decayed by d before it can't exceed 1. / (1. - d)
+    #[test]
+    fn decayed_lstm_state_stays_bounded() {
+        let _turn = crate::tests::backend_turn();
+        let decay = 0.5;
+        let max_cell = |state: &(Tensor<BACKEND, 2>, Tensor<BACKEND, 2>)| state.0.clone().abs().max().into_scalar();
+
+        let mut model = sumfxlstm::SumFxLstmModel::<BACKEND>::standard_model(&DEVICE);
+        model.state_decay = decay;
+        let full = Tensor::<BACKEND, 2>::full([1, model.intermediate_dim], 1000., &DEVICE);
+        model.state = (full.clone(), full);
+
+        for n in 0..50 {
+            let [being, fo, speechlet, own] = inputs(model.input_widths());
+            model.forward(being, fo, speechlet, own);
+            if n == 0 {
+                assert!(max_cell(&model.state) <= 1000. * decay + 1.);
+            }
+        }
+        assert!(max_cell(&model.state) < 1. / (1. - decay));
+    }
+
+
+    #[test]
+    fn a_zero_rate_branch_is_unchanged_by_mutate() {
+        let _turn = crate::tests::backend_turn();
+        let profile = MutationProfile { being: 0., ..MutationProfile::UNIFORM };
+
+        let model = sumfx::SumFxModel::<BACKEND>::standard_model(&DEVICE);
+        let mutated = model.clone().mutate_under(0.5, profile, &DEVICE);
+
+        assert_eq!(mutated.being_model.params(), model.being_model.params());
+        assert_ne!(mutated.fo_model.params(), model.fo_model.params());
+    }
+
+
+    // every branch maps its row to 8, and the final model maps the four concatenated to the outputs
+    #[test]
+    fn standard_models_count_their_params_by_hand() {
+        let _turn = crate::tests::backend_turn();
+        let branches: usize = SensorySchema::standard().widths().iter().map(|w| (w + 1) * 8).sum();
+        let ffs = branches + (32 + 1) * B_OUTPUT_LEN;
+        // four gates, each an input and a hidden transform with biases, 32 wide throughout
+        let lstm = 4 * 2 * (32 * 32 + 32);
+
+        assert_eq!(sumfx::SumFxModel::<BACKEND>::standard_model(&DEVICE).num_params(), ffs);
+        assert_eq!(sumfxlstm::SumFxLstmModel::<BACKEND>::standard_model(&DEVICE).num_params(), ffs + lstm);
+    }
+
+
+    // the lstm sits between the merge and the final model, so only the branches can be compared
+    #[test]
+    fn from_sumfx_carries_the_branch_outputs_over() {
+        let _turn = crate::tests::backend_turn();
+        let sumfx = sumfx::SumFxModel::<BACKEND>::standard_model(&DEVICE);
+        let converted = sumfxlstm::SumFxLstmModel::from_sumfx(sumfx.clone(), &DEVICE);
+        assert_eq!(converted.input_widths(), sumfx.input_widths());
+
+        let [being, fo, speechlet, own] = inputs(sumfx.input_widths());
+        let branches = [
+            (&sumfx.being_model, &converted.being_model, being),
+            (&sumfx.fo_model, &converted.fo_model, fo),
+            (&sumfx.speechlet_model, &converted.speechlet_model, speechlet),
+            (&sumfx.self_model, &converted.self_model, own),
+        ];
+        for (before, after, x) in branches {
+            assert_eq!(before.forward(x.clone()).into_data().value, after.forward(x).into_data().value);
+        }
+        assert_eq!(converted.final_model.params(), sumfx.final_model.params());
+    }
+
+
+    #[test]
+    fn a_switched_off_branch_ignores_its_inputs() {
+        let _turn = crate::tests::backend_turn();
+        let deaf = Senses { speechlet: false, ..Senses::ALL };
+        let mut model = sumfx::SumFxModel::<BACKEND>::standard_model(&DEVICE);
+        let [being, fo, speechlet, own] = inputs(model.input_widths());
+        let loud = speechlet.clone() * 5.;
+
+        let mut heard = |senses: Senses, speechlet: Tensor<BACKEND, 2>| {
+            let output = model.forward_under(senses, being.clone(), fo.clone(), speechlet, own.clone());
+            output.into_data().value
+        };
+
+        assert_eq!(heard(deaf, speechlet.clone()), heard(deaf, loud.clone()));
+        assert_ne!(heard(Senses::ALL, speechlet), heard(Senses::ALL, loud));
+    }
+}
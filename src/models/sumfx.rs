@@ -5,12 +5,13 @@ use burn::nn::Linear;
 use burn::prelude::*;
 use nn::LinearConfig;
 
-use burn::module::{Module, Param};
+use burn::module::Param;
 use burn::tensor::backend::Backend;
-use burn::tensor::{activation, BasicOps, Numeric, Tensor};
+use burn::tensor::{activation, BasicOps, Distribution, ElementConversion, Numeric, Tensor};
 
-use crate::being_nn::{combine_linears, Activation, Tanh, FF};
-use crate::{splice_ffs, B_OUTPUT_LEN, GENOME_LEN, SPEECHLET_LEN};
+use crate::being_nn::{combine_linears, describe_model, merge_branches, pool_set, sense_branch, Activation, CrossoverGranularity, MutationProfile, Senses, Tanh, FF};
+use crate::schema::SensorySchema;
+use crate::{splice_ffs, B_OUTPUT_LEN, CROSSOVER_GRANULARITY, MERGE_FLIP_RATE, MUTATION_PROFILE, SENSES};
 
 #[derive(Clone)]
 pub struct SumFxModel<B: Backend> {
@@ -75,20 +76,22 @@ impl<B: Backend> SumFxModel<B> {
     }
 
     pub fn standard_model(device: &Device<B>) -> Self {
+        let [being_width, fo_width, speechlet_width, own_width] = SensorySchema::standard().widths();
+
         let being_config = (
-            vec![3 + GENOME_LEN, 8],
+            vec![being_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let fo_config = (
-            vec![5, 8],
+            vec![fo_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let speechlet_config = (
-            vec![SPEECHLET_LEN, 8],
+            vec![speechlet_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let self_config = (
-            vec![5, 8],
+            vec![own_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let final_config = (
@@ -106,6 +109,48 @@ impl<B: Backend> SumFxModel<B> {
         );
     }
 
+    // the row width each sensory branch takes, in SensorySchema::widths order
+    pub fn input_widths(&self) -> [usize; 4] {
+        [
+            self.being_model.config.0[0],
+            self.fo_model.config.0[0],
+            self.speechlet_model.config.0[0],
+            self.self_model.config.0[0],
+        ]
+    }
+
+    // every weight and bias the model carries, across all of its branches
+    pub fn num_params(&self) -> usize {
+        [
+            &self.being_model,
+            &self.fo_model,
+            &self.speechlet_model,
+            &self.self_model,
+            &self.final_model,
+        ]
+        .iter()
+        .map(|ff| ff.num_params())
+        .sum::<usize>()
+    }
+
+    // see describe_model
+    pub fn architecture(&self) -> String {
+        describe_model(
+            "SumFxModel",
+            self.concat_before_final,
+            self.intermediate_dim,
+            [
+                &self.being_model,
+                &self.fo_model,
+                &self.speechlet_model,
+                &self.self_model,
+                &self.final_model,
+            ],
+            &[],
+            self.num_params(),
+        )
+    }
+
     pub fn forward(
         &mut self,
         being_tensor: Tensor<B, 2>,
@@ -113,21 +158,32 @@ impl<B: Backend> SumFxModel<B> {
         speechlet_tensor: Tensor<B, 2>,
         self_tensor: Tensor<B, 2>,
     ) -> Tensor<B, 1> {
-        let beings_output = self.being_model.forward(being_tensor).mean_dim(0);
-        let fo_output = self.fo_model.forward(fo_tensor).mean_dim(0);
-        let speechlet_output = self.speechlet_model.forward(speechlet_tensor).mean_dim(0);
-        let self_output = self.self_model.forward(self_tensor);
-
-        let intermediate: Tensor<B, 2> = {
-            if self.concat_before_final {
-                Tensor::cat(
-                    vec![beings_output, fo_output, speechlet_output, self_output],
-                    1,
-                )
-            } else {
-                (beings_output + fo_output + speechlet_output + self_output) / 4.
-            }
-        };
+        self.forward_under(SENSES, being_tensor, fo_tensor, speechlet_tensor, self_tensor)
+    }
+
+    pub(crate) fn forward_under(
+        &mut self,
+        senses: Senses,
+        being_tensor: Tensor<B, 2>,
+        fo_tensor: Tensor<B, 2>,
+        speechlet_tensor: Tensor<B, 2>,
+        self_tensor: Tensor<B, 2>,
+    ) -> Tensor<B, 1> {
+        let beings_output = sense_branch(senses.being, &self.being_model, being_tensor, |x| {
+            pool_set(self.being_model.forward(x))
+        });
+        let fo_output = sense_branch(senses.food_obstruct, &self.fo_model, fo_tensor, |x| {
+            pool_set(self.fo_model.forward(x))
+        });
+        let speechlet_output = sense_branch(senses.speechlet, &self.speechlet_model, speechlet_tensor, |x| {
+            pool_set(self.speechlet_model.forward(x))
+        });
+        let self_output = sense_branch(senses.own, &self.self_model, self_tensor, |x| self.self_model.forward(x));
+
+        let intermediate = merge_branches(
+            [beings_output, fo_output, speechlet_output, self_output],
+            self.concat_before_final,
+        );
 
         let final_output = self.final_model.forward(intermediate).squeeze(0);
         let final_output = activation::tanh(final_output);
@@ -135,36 +191,79 @@ impl<B: Backend> SumFxModel<B> {
         final_output
     }
 
+    // switches between concatenating and adding the branch outputs. only the final FF's first layer changes
+    // width: going to concat it's tiled once per branch and quartered, which computes just what add mode's
+    // average did, and going to add its per-branch blocks are summed, which undoes that. false, and nothing
+    // changes, if the branches are too unequal to add
+    pub fn set_mode(&mut self, concat: bool, device: &Device<B>) -> bool {
+        if concat == self.concat_before_final {
+            return true;
+        }
+
+        let widths = [&self.being_model, &self.fo_model, &self.speechlet_model, &self.self_model]
+            .map(|ff| *ff.config.0.last().unwrap());
+        if !concat && widths.iter().any(|w| *w != widths[0]) {
+            return false;
+        }
+
+        let first = &mut self.final_model.lins[0];
+        let weight = first.weight.val();
+        let [d_in, d_out] = weight.dims();
+        let weight = if concat {
+            Tensor::cat(vec![weight; 4], 0) / 4.
+        } else {
+            weight.reshape([4, d_in / 4, d_out]).sum_dim(0).reshape([d_in / 4, d_out])
+        };
+        first.weight = Param::from_tensor(weight.to_device(device));
+
+        self.intermediate_dim = if concat { widths.iter().sum() } else { widths[0] };
+        self.final_model.config.0[0] = self.intermediate_dim;
+        self.concat_before_final = concat;
+
+        true
+    }
+
+    // a mate in the other merge mode is brought over to this one's first. one that can't be has nothing to
+    // splice in, and self is passed on as it is
     pub fn crossover(
         self,
-        other: SumFxModel<B>,
+        mut other: SumFxModel<B>,
         crossover_weight: f32,
         device: &Device<B>,
     ) -> Self {
+        if !other.set_mode(self.concat_before_final, device) {
+            return self;
+        }
+
         let being_model = splice_ffs(
             self.being_model,
             other.being_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let fo_model = splice_ffs(
             self.fo_model,
             other.fo_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let speechlet_model = splice_ffs(
             self.speechlet_model,
             other.speechlet_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let self_model = splice_ffs(
             self.self_model,
             other.self_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let final_model = splice_ffs(
             self.final_model,
             other.final_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
 
         return SumFxModel {
@@ -180,23 +279,31 @@ impl<B: Backend> SumFxModel<B> {
     }
 
     pub fn mutate(self, mutation_rate: f32, device: &Device<B>) -> Self {
+        self.mutate_under(mutation_rate, MUTATION_PROFILE, device)
+    }
+
+    // as mutate, with the rate scaled per branch by `profile`
+    pub fn mutate_under(self, mutation_rate: f32, profile: MutationProfile, device: &Device<B>) -> Self {
         let mut new_models: Vec<FF<B>> = vec![];
 
-        for model in [
-            self.being_model,
-            self.fo_model,
-            self.speechlet_model,
-            self.self_model,
-            self.final_model,
-        ] {
+        for (model, scale) in zip(
+            [
+                self.being_model,
+                self.fo_model,
+                self.speechlet_model,
+                self.self_model,
+                self.final_model,
+            ],
+            profile.ff_scales(),
+        ) {
             let config = model.config.clone();
             let mutation_model = FF::new(config.0, config.1, device);
 
-            let model = splice_ffs(model, mutation_model, 1. - mutation_rate);
+            let model = splice_ffs(model, mutation_model, 1. - mutation_rate * scale, CrossoverGranularity::Weight);
             new_models.push(model.clone());
         }
 
-        return SumFxModel {
+        let mut mutated = SumFxModel {
             being_model: new_models[0].to_owned(),
             fo_model: new_models[1].to_owned(),
             speechlet_model: new_models[2].to_owned(),
@@ -206,5 +313,74 @@ impl<B: Backend> SumFxModel<B> {
             concat_before_final: self.concat_before_final,
             intermediate_dim: self.intermediate_dim,
         };
+        // drawn from the backend like the mutation itself, so a seeded backend flips reproducibly
+        let flip: f32 = Tensor::<B, 1>::random([1], Distribution::Uniform(0., 1.), device).into_scalar().elem();
+        if flip < MERGE_FLIP_RATE {
+            let concat = !mutated.concat_before_final;
+            mutated.set_mode(concat, device);
+        }
+
+        mutated
+    }
+
+    // every FF's parameters back to back, see FF::params
+    pub fn params(&self) -> Vec<f32> {
+        [
+            &self.being_model,
+            &self.fo_model,
+            &self.speechlet_model,
+            &self.self_model,
+            &self.final_model,
+        ]
+        .iter()
+        .flat_map(|ff| ff.params())
+        .collect()
+    }
+
+    // overwrites the parameters with those of a model of the same shape, as given by params
+    pub fn load_params(mut self, params: &[f32]) -> Self {
+        let mut n = 0;
+        for ff in [
+            &mut self.being_model,
+            &mut self.fo_model,
+            &mut self.speechlet_model,
+            &mut self.self_model,
+            &mut self.final_model,
+        ] {
+            n += ff.load_params(&params[n..]);
+        }
+        assert_eq!(n, params.len(), "parameter count doesn't match the model's shape");
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BACKEND, DEVICE};
+
+    fn forward(model: &mut SumFxModel<BACKEND>) -> Vec<f32> {
+        let [being, fo, speechlet, own] = model.input_widths().map(|w| Tensor::<BACKEND, 2>::ones([2, w], &DEVICE));
+        let own = own.narrow(0, 0, 1);
+        model.forward(being, fo, speechlet, own).into_data().convert::<f32>().value
+    }
+
+    #[test]
+    fn flipped_models_still_run_and_compute_the_same() {
+        let _turn = crate::tests::backend_turn();
+        let mut model = SumFxModel::<BACKEND>::standard_model(&DEVICE);
+        assert!(model.concat_before_final);
+
+        assert!(model.set_mode(false, &DEVICE));
+        let added = forward(&mut model);
+        assert_eq!(added.len(), B_OUTPUT_LEN);
+
+        assert!(model.set_mode(true, &DEVICE));
+        let concatenated = forward(&mut model);
+        assert_eq!(model.final_model.lins[0].weight.val().dims()[0], model.intermediate_dim);
+        for (a, c) in zip(added, concatenated) {
+            assert!((a - c).abs() < 1e-5, "{} vs {}", a, c);
+        }
     }
 }
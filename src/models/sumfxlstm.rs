@@ -10,8 +10,10 @@ use burn::nn::Relu;
 use burn::tensor::backend::Backend;
 use burn::tensor::{activation, Tensor};
 
-use crate::being_nn::{combine_linears, combine_lstms, Activation, Tanh, FF};
-use crate::{splice_ffs, B_OUTPUT_LEN, GENOME_LEN, SPEECHLET_LEN};
+use crate::being_nn::{combine_linears, combine_lstms, describe_model, merge_branches, pool_set, sense_branch, Activation, CrossoverGranularity, MutationProfile, Tanh, FF};
+use crate::models::sumfx::SumFxModel;
+use crate::schema::SensorySchema;
+use crate::{splice_ffs, B_OUTPUT_LEN, CROSSOVER_GRANULARITY, LSTM_STATE_DECAY, MUTATION_PROFILE, SENSES};
 
 #[derive(Clone)]
 pub struct SumFxLstmModel<B: Backend> {
@@ -26,8 +28,9 @@ pub struct SumFxLstmModel<B: Backend> {
     pub concat_before_final: bool,
     pub intermediate_dim: usize,
     pub lstm_inp_size: usize,
+    pub state_decay: f32, // gives memory a half-life of ln(0.5) / ln(state_decay) steps
 
-    state: (Tensor<B, 2>, Tensor<B, 2>),
+    pub(super) state: (Tensor<B, 2>, Tensor<B, 2>),
 }
 
 impl<B: Backend> SumFxLstmModel<B> {
@@ -39,6 +42,7 @@ impl<B: Backend> SumFxLstmModel<B> {
         final_config: (Vec<usize>, Vec<Activation>),
 
         concat_before_final: bool,
+        state_decay: f32,
 
         device: &Device<B>,
     ) -> Self {
@@ -91,6 +95,33 @@ impl<B: Backend> SumFxLstmModel<B> {
             concat_before_final: concat_before_final,
             intermediate_dim: intermediate_dim,
             lstm_inp_size: lstm_inp_size,
+            state_decay: state_decay,
+            state: (
+                Tensor::<B, 2>::zeros([1, intermediate_dim], device).no_grad(),
+                Tensor::<B, 2>::zeros([1, intermediate_dim], device).no_grad(),
+            ),
+        }
+    }
+
+    // carries an evolved SumFxModel's branches over as they are, with a fresh LSTM in between. the LSTM sits
+    // on the intermediate, so it is as wide as the SumFxModel's intermediate either way
+    pub fn from_sumfx(model: SumFxModel<B>, device: &Device<B>) -> Self {
+        let intermediate_dim = model.intermediate_dim;
+
+        SumFxLstmModel {
+            being_model: model.being_model,
+            fo_model: model.fo_model,
+            speechlet_model: model.speechlet_model,
+            self_model: model.self_model,
+            lstm: LstmConfig::new(intermediate_dim, intermediate_dim, true)
+                .init(device)
+                .no_grad(),
+            final_model: model.final_model,
+
+            concat_before_final: model.concat_before_final,
+            intermediate_dim: intermediate_dim,
+            lstm_inp_size: intermediate_dim,
+            state_decay: LSTM_STATE_DECAY,
             state: (
                 Tensor::<B, 2>::zeros([1, intermediate_dim], device).no_grad(),
                 Tensor::<B, 2>::zeros([1, intermediate_dim], device).no_grad(),
@@ -99,20 +130,22 @@ impl<B: Backend> SumFxLstmModel<B> {
     }
 
     pub fn standard_model(device: &Device<B>) -> Self {
+        let [being_width, fo_width, speechlet_width, own_width] = SensorySchema::standard().widths();
+
         let being_config = (
-            vec![3 + GENOME_LEN, 8],
+            vec![being_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let fo_config = (
-            vec![5, 8],
+            vec![fo_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let speechlet_config = (
-            vec![SPEECHLET_LEN, 8],
+            vec![speechlet_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let self_config = (
-            vec![5, 8],
+            vec![own_width, 8],
             vec![Activation::Tanh(Tanh {}), Activation::Tanh(Tanh {})],
         );
         let final_config = (
@@ -126,10 +159,60 @@ impl<B: Backend> SumFxLstmModel<B> {
             self_config,
             final_config,
             true,
+            LSTM_STATE_DECAY,
             device,
         );
     }
 
+    // the row width each sensory branch takes, in SensorySchema::widths order
+    pub fn input_widths(&self) -> [usize; 4] {
+        [
+            self.being_model.config.0[0],
+            self.fo_model.config.0[0],
+            self.speechlet_model.config.0[0],
+            self.self_model.config.0[0],
+        ]
+    }
+
+    // every weight and bias the model carries, across all of its branches
+    pub fn num_params(&self) -> usize {
+        [
+            &self.being_model,
+            &self.fo_model,
+            &self.speechlet_model,
+            &self.self_model,
+            &self.final_model,
+        ]
+        .iter()
+        .map(|ff| ff.num_params())
+        .sum::<usize>()
+            + self.lstm.num_params()
+    }
+
+    // see describe_model
+    pub fn architecture(&self) -> String {
+        describe_model(
+            "SumFxLstmModel",
+            self.concat_before_final,
+            self.intermediate_dim,
+            [
+                &self.being_model,
+                &self.fo_model,
+                &self.speechlet_model,
+                &self.self_model,
+                &self.final_model,
+            ],
+            &[
+                (
+                    "lstm",
+                    format!("{} -> {}", self.lstm_inp_size, self.lstm_inp_size),
+                    self.lstm.num_params(),
+                ),
+            ],
+            self.num_params(),
+        )
+    }
+
     pub fn forward(
         &mut self,
         being_tensor: Tensor<B, 2>,
@@ -137,25 +220,29 @@ impl<B: Backend> SumFxLstmModel<B> {
         speechlet_tensor: Tensor<B, 2>,
         self_tensor: Tensor<B, 2>,
     ) -> Tensor<B, 1> {
-        let beings_output = self.being_model.forward(being_tensor).mean_dim(0);
-        let fo_output = self.fo_model.forward(fo_tensor).mean_dim(0);
-        let speechlet_output = self.speechlet_model.forward(speechlet_tensor).mean_dim(0);
-        let self_output = self.self_model.forward(self_tensor);
-
-        let intermediate: Tensor<B, 2> = {
-            if self.concat_before_final {
-                Tensor::cat(
-                    vec![beings_output, fo_output, speechlet_output, self_output],
-                    1,
-                )
-            } else {
-                (beings_output + fo_output + speechlet_output + self_output) / 4.
-            }
-        };
+        let beings_output = sense_branch(SENSES.being, &self.being_model, being_tensor, |x| {
+            pool_set(self.being_model.forward(x))
+        });
+        let fo_output = sense_branch(SENSES.food_obstruct, &self.fo_model, fo_tensor, |x| {
+            pool_set(self.fo_model.forward(x))
+        });
+        let speechlet_output = sense_branch(SENSES.speechlet, &self.speechlet_model, speechlet_tensor, |x| {
+            pool_set(self.speechlet_model.forward(x))
+        });
+        let self_output = sense_branch(SENSES.own, &self.self_model, self_tensor, |x| self.self_model.forward(x));
+
+        let intermediate = merge_branches(
+            [beings_output, fo_output, speechlet_output, self_output],
+            self.concat_before_final,
+        );
 
+        let state = (
+            self.state.0.clone().mul_scalar(self.state_decay),
+            self.state.1.clone().mul_scalar(self.state_decay),
+        );
         let (c, h) = self
             .lstm
-            .forward(intermediate.clone().unsqueeze(), Some(self.state.clone()));
+            .forward(intermediate.clone().unsqueeze(), Some(state));
 
         let (c, h): (Tensor<B, 2>, Tensor<B, 2>) = (c.squeeze(0).no_grad(), h.squeeze(0).no_grad());
         self.state = (c.clone(), h.clone());
@@ -176,26 +263,31 @@ impl<B: Backend> SumFxLstmModel<B> {
             self.being_model,
             other.being_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let fo_model = splice_ffs(
             self.fo_model,
             other.fo_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let speechlet_model = splice_ffs(
             self.speechlet_model,
             other.speechlet_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let self_model = splice_ffs(
             self.self_model,
             other.self_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
         let final_model = splice_ffs(
             self.final_model,
             other.final_model,
             crossover_weight,
+            CROSSOVER_GRANULARITY,
         );
 
         return SumFxLstmModel {
@@ -215,6 +307,7 @@ impl<B: Backend> SumFxLstmModel<B> {
             concat_before_final: self.concat_before_final,
             intermediate_dim: self.intermediate_dim,
             lstm_inp_size: self.lstm_inp_size,
+            state_decay: self.state_decay,
             state: (
                 Tensor::<B, 2>::zeros([1, self.intermediate_dim as usize], device),
                 Tensor::<B, 2>::zeros([1, self.intermediate_dim as usize], device),
@@ -222,18 +315,26 @@ impl<B: Backend> SumFxLstmModel<B> {
         };
     }
     pub fn mutate(self, mutation_rate: f32, device: &Device<B>) -> Self {
+        self.mutate_under(mutation_rate, MUTATION_PROFILE, device)
+    }
+
+    // as mutate, with the rate scaled per branch by `profile`
+    pub fn mutate_under(self, mutation_rate: f32, profile: MutationProfile, device: &Device<B>) -> Self {
         let mut new_models: Vec<FF<B>> = vec![];
 
-        for model in [
-            self.being_model,
-            self.fo_model,
-            self.speechlet_model,
-            self.self_model,
-            self.final_model,
-        ] {
+        for (model, scale) in zip(
+            [
+                self.being_model,
+                self.fo_model,
+                self.speechlet_model,
+                self.self_model,
+                self.final_model,
+            ],
+            profile.ff_scales(),
+        ) {
             let config = model.config.clone();
             let mutation_model = FF::new(config.0, config.1, device);
-            let new_model = splice_ffs(model, mutation_model, 1. - mutation_rate);
+            let new_model = splice_ffs(model, mutation_model, 1. - mutation_rate * scale, CrossoverGranularity::Weight);
             new_models.push(new_model);
         }
 
@@ -245,12 +346,13 @@ impl<B: Backend> SumFxLstmModel<B> {
             fo_model: new_models[1].to_owned(),
             speechlet_model: new_models[2].to_owned(),
             self_model: new_models[3].to_owned(),
-            lstm: combine_lstms(self.lstm, mutation_lstm, 1., mutation_rate),
+            lstm: combine_lstms(self.lstm, mutation_lstm, 1., mutation_rate * profile.lstm),
             final_model: new_models[4].to_owned(),
 
             concat_before_final: self.concat_before_final,
             intermediate_dim: self.intermediate_dim,
             lstm_inp_size: self.lstm_inp_size,
+            state_decay: self.state_decay,
             state: (
                 Tensor::<B, 2>::zeros([1, self.intermediate_dim as usize], device),
                 Tensor::<B, 2>::zeros([1, self.intermediate_dim as usize], device),
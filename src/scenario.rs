@@ -0,0 +1,107 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+// (being, food_obstruct, speechlet) rows, as World::debug_forward takes them
+pub type Observation = (Vec<Vec<f32>>, Vec<Vec<f32>>, Vec<Vec<f32>>);
+
+// a fixed run of hand-made observations and the outputs a known-good model gave for them. replaying it against
+// the same model after a refactor, or against a reloaded one, shows whether its behaviour has moved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub steps: Vec<ScenarioStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioStep {
+    pub being: Vec<Vec<f32>>,
+    pub food_obstruct: Vec<Vec<f32>>,
+    pub speechlet: Vec<Vec<f32>>,
+    pub expected: Vec<f32>,
+}
+
+// where a replay first parted from the recording
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioMismatch {
+    // the model put out a different number of outputs than were recorded, as a reshaped model would
+    Width {
+        step: usize,
+        expected: usize,
+        actual: usize,
+    },
+    // an output strayed further than the tolerance
+    Output {
+        step: usize,
+        output: usize,
+        expected: f32,
+        actual: f32,
+    },
+}
+
+impl Scenario {
+    // the golden recording: whatever `forward` puts out for each observation is taken to be right
+    pub fn record(
+        observations: Vec<Observation>,
+        mut forward: impl FnMut(Observation) -> Vec<f32>,
+    ) -> Self {
+        let steps = observations
+            .into_iter()
+            .map(|(being, food_obstruct, speechlet)| {
+                let expected = forward((being.clone(), food_obstruct.clone(), speechlet.clone()));
+                ScenarioStep {
+                    being,
+                    food_obstruct,
+                    speechlet,
+                    expected,
+                }
+            })
+            .collect();
+
+        Scenario { steps }
+    }
+
+    // replays every observation, in order, against `forward`
+    pub fn check(
+        &self,
+        mut forward: impl FnMut(Observation) -> Vec<f32>,
+        tolerance: f32,
+    ) -> Result<(), ScenarioMismatch> {
+        for (n, step) in self.steps.iter().enumerate() {
+            let actual = forward((
+                step.being.clone(),
+                step.food_obstruct.clone(),
+                step.speechlet.clone(),
+            ));
+            if actual.len() != step.expected.len() {
+                return Err(ScenarioMismatch::Width {
+                    step: n,
+                    expected: step.expected.len(),
+                    actual: actual.len(),
+                });
+            }
+
+            for (i, (expected, actual)) in step.expected.iter().zip(&actual).enumerate() {
+                if (expected - actual).abs() > tolerance {
+                    return Err(ScenarioMismatch::Output {
+                        step: n,
+                        output: i,
+                        expected: *expected,
+                        actual: *actual,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).expect("a scenario is always serializable");
+        fs::write(path, json)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
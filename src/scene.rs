@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+// a flat, model-free picture of the world at one instant, for external viewers and analysis. not enough to
+// resume from; see archive for that
+#[derive(Debug, Clone, Serialize)]
+pub struct Scene {
+    pub age: usize,
+    pub generation: usize,
+    pub entities: Vec<SceneEntity>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SceneEntity {
+    pub kind: &'static str, // "being", "food", "obstruct", "speechlet" or "corpse"
+    pub id: Option<u64>, // the same entity keeps its id from scene to scene; speechlets have none
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub rotation: f32,       // 0. for everything but beings
+    pub energy: Option<f32>, // a being's or corpse's energy, or a food's value
+    pub age: Option<f32>,    // an obstruct's or speechlet's age
+}
+
+impl Scene {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a scene is always serializable")
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    EmptyInputPolicy, EMPTY_INPUT_POLICY, GENOME_LEN, PHEROMONES, SENSE_DAMAGE, SENSE_POPULATION,
+    SENSE_TERRAIN, SEPARATE_HEALTH, SPEECHLET_LEN, VISION_MEMORY_DECAY, WALL_SENSE,
+};
+
+// the channels of each sensory branch, in the order they're laid out in a row. a model's input sizes and the
+// world's observation tensors are both sized from this, so a new channel is declared once, here, and
+// assembled in perform_being_outputs in the same order
+#[derive(Debug, Clone)]
+pub struct SensorySchema {
+    pub being: Vec<&'static str>,         // one row per being in sight
+    pub food_obstruct: Vec<&'static str>, // one row per food or obstruct in sight
+    pub speechlet: Vec<&'static str>,     // one row per speechlet heard
+    pub own: Vec<&'static str>,           // the being's single row about itself
+}
+
+impl SensorySchema {
+    // the schema the current config calls for
+    pub fn standard() -> Self {
+        let mut being = vec!["angle", "distance", "energy"];
+        being.extend(["genome"; GENOME_LEN]);
+
+        let mut own = WALL_SENSE.channels().to_vec();
+        own.push("energy");
+        if let EmptyInputPolicy::Flag = EMPTY_INPUT_POLICY {
+            own.extend(["no_beings", "no_food_obstructs", "no_speechlets"]);
+        }
+        if VISION_MEMORY_DECAY.is_some() {
+            own.extend([
+                "food_memory_angle",
+                "food_memory_distance",
+                "food_memory_strength",
+                "being_memory_angle",
+                "being_memory_distance",
+                "being_memory_strength",
+            ]);
+        }
+        if SENSE_DAMAGE {
+            own.push("damage_taken");
+        }
+        if SEPARATE_HEALTH {
+            own.push("health");
+        }
+        if SENSE_TERRAIN {
+            own.extend(["terrain_speed", "terrain_tire"]);
+        }
+        if PHEROMONES {
+            own.extend(["pheromone", "pheromone_ahead", "pheromone_across"]);
+        }
+        if SENSE_POPULATION {
+            own.push("crowding");
+        }
+
+        SensorySchema {
+            being: being,
+            food_obstruct: vec!["is_food", "distance", "angle", "strength", "age"],
+            speechlet: vec!["speechlet"; SPEECHLET_LEN],
+            own: own,
+        }
+    }
+
+    // (being, food_obstruct, speechlet, own) row widths, the order models take their inputs in
+    pub fn widths(&self) -> [usize; 4] {
+        [
+            self.being.len(),
+            self.food_obstruct.len(),
+            self.speechlet.len(),
+            self.own.len(),
+        ]
+    }
+}
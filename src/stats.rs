@@ -0,0 +1,251 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+    time::Duration,
+};
+
+// where a headless run ended up, see World::run_headless. two runs of one seeded world agree on all of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    pub steps: usize,
+    pub generation: usize,
+    pub beings: usize,
+    pub foods: usize,
+    pub obstructs: usize,
+    pub speechlets: usize,
+    pub corpses: usize,
+    pub energy: f32, // summed over the living beings
+}
+
+// how often each action fired over one generation, tallied from the beings' outputs
+#[derive(Debug, Clone, Default)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub steps: usize,
+    pub being_steps: usize, // sum of the population over every step, the denominator for the rest
+
+    pub moves_forward: usize,
+    pub moves_backward: usize,
+    pub rotation_magnitude: f32, // total radians turned, regardless of direction
+    pub obstruct_spawns: usize,
+    pub speechlet_emissions: usize,
+    pub eats: usize,
+}
+
+impl GenerationStats {
+    pub fn new(generation: usize) -> Self {
+        GenerationStats {
+            generation: generation,
+            ..Default::default()
+        }
+    }
+
+    pub fn csv_header() -> &'static str {
+        "generation,steps,being_steps,moves_forward,moves_backward,rotation_magnitude,obstruct_spawns,speechlet_emissions,eats"
+    }
+
+    pub fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{}",
+            self.generation,
+            self.steps,
+            self.being_steps,
+            self.moves_forward,
+            self.moves_backward,
+            self.rotation_magnitude,
+            self.obstruct_spawns,
+            self.speechlet_emissions,
+            self.eats,
+        )
+    }
+
+    // the inverse of csv_row, None for a malformed row
+    pub fn from_csv_row(row: &str) -> Option<Self> {
+        let fields: Vec<&str> = row.trim().split(',').collect();
+        if fields.len() != 9 {
+            return None;
+        }
+
+        Some(GenerationStats {
+            generation: fields[0].parse().ok()?,
+            steps: fields[1].parse().ok()?,
+            being_steps: fields[2].parse().ok()?,
+            moves_forward: fields[3].parse().ok()?,
+            moves_backward: fields[4].parse().ok()?,
+            rotation_magnitude: fields[5].parse().ok()?,
+            obstruct_spawns: fields[6].parse().ok()?,
+            speechlet_emissions: fields[7].parse().ok()?,
+            eats: fields[8].parse().ok()?,
+        })
+    }
+
+    // appends a row, writing the header first if the file is new
+    pub fn append_csv(&self, path: &Path) -> io::Result<()> {
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        if is_new {
+            writeln!(file, "{}", Self::csv_header())?;
+        }
+        writeln!(file, "{}", self.csv_row())
+    }
+}
+
+// wall-clock time spent in each phase of World::step, summed over `steps` steps
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhaseTimings {
+    pub steps: usize,
+    pub moving: Duration,
+    pub collision: Duration, // collision checks, perception included, and cell updates
+    pub forward: Duration,   // perform_being_outputs, model inference included
+    pub aging: Duration,     // the per-entity passes that follow, tallies and trajectories included
+    pub reworld: Duration,   // food regrowth, immigration and reworlding
+}
+
+impl PhaseTimings {
+    // mean milliseconds per step, in field order
+    pub fn per_step_ms(&self) -> [f32; 5] {
+        let steps = self.steps.max(1) as f32;
+        [
+            self.moving,
+            self.collision,
+            self.forward,
+            self.aging,
+            self.reworld,
+        ]
+        .map(|total| total.as_secs_f32() * 1000. / steps)
+    }
+
+    pub fn summary(&self) -> String {
+        let [moving, collision, forward, aging, reworld] = self.per_step_ms();
+        format!(
+            "ms/step move {:.2} collide {:.2} forward {:.2} age {:.2} reworld {:.2}",
+            moving, collision, forward, aging, reworld
+        )
+    }
+}
+
+// how evenly beings spread over the partition grid. a heavy skew means the grid isn't pruning much
+// and collision checks are drifting towards O(n²)
+#[derive(Debug, Clone, Default)]
+pub struct OccupancyStats {
+    pub max: usize,
+    pub mean: f32,
+    pub variance: f32,
+    pub empty_fraction: f32,
+}
+
+impl OccupancyStats {
+    pub fn from_counts(counts: &[usize]) -> Self {
+        let n = counts.len() as f32;
+        let mean = counts.iter().sum::<usize>() as f32 / n;
+
+        OccupancyStats {
+            max: counts.iter().copied().max().unwrap_or(0),
+            mean: mean,
+            variance: counts
+                .iter()
+                .map(|c| (*c as f32 - mean).powi(2))
+                .sum::<f32>()
+                / n,
+            empty_fraction: counts.iter().filter(|c| **c == 0).count() as f32 / n,
+        }
+    }
+}
+
+// the standard deviation of each parameter across a population, averaged over the parameters; 0. for a
+// population of clones, or of fewer than two
+pub fn weight_diversity(params: &[Vec<f32>]) -> f32 {
+    let (n, Some(len)) = (params.len(), params.first().map(|p| p.len())) else {
+        return 0.;
+    };
+    if n < 2 || len == 0 {
+        return 0.;
+    }
+
+    let mut total = 0.;
+    for i in 0..len {
+        let mean = params.iter().map(|p| p[i]).sum::<f32>() / n as f32;
+        let var = params.iter().map(|p| (p[i] - mean).powi(2)).sum::<f32>() / n as f32;
+        total += var.sqrt();
+    }
+    total / len as f32
+}
+
+// 0. for fewer than two samples or a constant series, rather than NaN
+pub fn pearson(xs: &[f32], ys: &[f32]) -> f32 {
+    let n = xs.len() as f32;
+    if xs.len() < 2 {
+        return 0.;
+    }
+
+    let (x_mean, y_mean) = (xs.iter().sum::<f32>() / n, ys.iter().sum::<f32>() / n);
+    let (mut cov, mut x_var, mut y_var) = (0., 0., 0.);
+    for (x, y) in xs.iter().zip(ys) {
+        cov += (x - x_mean) * (y - y_mean);
+        x_var += (x - x_mean).powi(2);
+        y_var += (y - y_mean).powi(2);
+    }
+
+    if x_var == 0. || y_var == 0. {
+        0.
+    } else {
+        cov / (x_var * y_var).sqrt()
+    }
+}
+
+// a generation worth a closer look, flagged by World::reworld as each one ends
+#[derive(Debug, Clone)]
+pub enum Anomaly {
+    Extinction {
+        generation: usize,
+        species: usize,
+    },
+    // the generation's being-steps fell under ANOMALY_DIE_OFF_FRACTION of the previous generation's
+    DieOff {
+        generation: usize,
+        being_steps: usize,
+        previous: usize,
+    },
+    // the generation outlasted every one before it
+    LongestGeneration {
+        generation: usize,
+        steps: usize,
+    },
+    // the population was mutated wholesale; `diversity` is its weight_diversity just before
+    Catastrophe {
+        generation: usize,
+        diversity: f32,
+    },
+}
+
+impl Anomaly {
+    // what `latest` says against the generations before it
+    pub fn from_stats(
+        history: &[GenerationStats],
+        latest: &GenerationStats,
+        die_off_fraction: f32,
+    ) -> Vec<Anomaly> {
+        let mut anomalies = vec![];
+        let Some(previous) = history.last() else {
+            return anomalies;
+        };
+
+        if (latest.being_steps as f32) < die_off_fraction * previous.being_steps as f32 {
+            anomalies.push(Anomaly::DieOff {
+                generation: latest.generation,
+                being_steps: latest.being_steps,
+                previous: previous.being_steps,
+            });
+        }
+        if history.iter().all(|stats| latest.steps > stats.steps) {
+            anomalies.push(Anomaly::LongestGeneration {
+                generation: latest.generation,
+                steps: latest.steps,
+            });
+        }
+
+        anomalies
+    }
+}
@@ -0,0 +1,31 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+// the order of the fingerprints in a StepHashes
+pub const SUBSYSTEMS: [&str; 5] = ["beings", "foods", "obstructs", "speechlets", "corpses"];
+
+// a fingerprint per subsystem of the world's state at the start of one step
+pub type StepHashes = [u64; 5];
+
+// hashes floats bit for bit, so even a last-place rounding difference shows
+pub fn hash_floats(values: impl Iterator<Item = f32>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    values.for_each(|v| v.to_bits().hash(&mut hasher));
+    hasher.finish()
+}
+
+// the first step at which two runs' traces disagree, with the subsystems that did. only the steps both runs
+// reached are compared
+pub fn first_divergence(a: &[StepHashes], b: &[StepHashes]) -> Option<(usize, Vec<&'static str>)> {
+    a.iter().zip(b).position(|(x, y)| x != y).map(|step| {
+        let subsystems = SUBSYSTEMS
+            .iter()
+            .zip(a[step].iter().zip(&b[step]))
+            .filter(|(_, (x, y))| x != y)
+            .map(|(name, _)| *name)
+            .collect();
+        (step, subsystems)
+    })
+}